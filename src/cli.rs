@@ -32,6 +32,10 @@ pub enum Command {
     Version(VersionCommand),
     /// Helps to make conventional commits.
     Commit(CommitCommand),
+    /// Bumps the version, updates the changelog, commits and tags the release
+    Release(ReleaseCommand),
+    /// Manages the git hooks that run convco
+    Hooks(HooksCommand),
 }
 
 #[derive(Debug, Parser)]
@@ -41,7 +45,7 @@ pub struct ConfigCommand {
     pub default: bool,
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 pub struct VersionCommand {
     /// Prefix used in front of the semantic version
     #[clap(short, long, default_value = "v", env = "CONVCO_PREFIX")]
@@ -86,6 +90,70 @@ pub struct VersionCommand {
     /// Treat major version zero as stable when calculating the next version. Requires --bump.
     #[clap(long, env = "CONVCO_TREAT_MAJOR_ZERO_AS_STABLE", requires = "bump")]
     pub treat_major_zero_as_stable: bool,
+    /// Print the version of every package from the `packages` config, one `<name> <version>` per line.
+    /// Each package uses its own paths and tag prefix.
+    #[clap(long, conflicts_with_all(&["prefix", "paths"]))]
+    pub all: bool,
+    /// Write the version to the `bumpFiles` from the config.
+    /// With --all the `bumpFiles` of every package are written.
+    #[clap(long, conflicts_with_all(&["label", "commit_sha"]))]
+    pub write: bool,
+    /// Report the tags that start with the prefix but are not version tags.
+    #[clap(long)]
+    pub verbose: bool,
+    /// List the commits that decide the bump on stderr: their increment,
+    /// whether they are breaking and the commits skipped by --paths. Requires --bump.
+    #[clap(long, requires = "bump")]
+    pub explain: bool,
+    #[clap(flatten)]
+    pub filters: CommitFilterArgs,
+}
+
+#[derive(Debug, Parser)]
+pub struct ReleaseCommand {
+    /// Prefix used in front of the semantic version
+    #[clap(short, long, default_value = "v", env = "CONVCO_PREFIX")]
+    pub prefix: String,
+    /// Bump to a major release version, regardless of the conventional commits
+    #[clap(long, env = "CONVCO_FORCE_MAJOR_BUMP", conflicts_with_all(&["minor", "patch"]))]
+    pub major: bool,
+    /// Bump to a minor release version, regardless of the conventional commits
+    #[clap(long, env = "CONVCO_FORCE_MINOR_BUMP", conflicts_with_all(&["major", "patch"]))]
+    pub minor: bool,
+    /// Bump to a patch release version, regardless of the conventional commits
+    #[clap(long, env = "CONVCO_FORCE_PATCH_BUMP", conflicts_with_all(&["major", "minor"]))]
+    pub patch: bool,
+    /// Suffix with a prerelease version.
+    #[clap(long, default_value_t = Prerelease::new("").unwrap())]
+    pub prerelease: Prerelease,
+    /// Only commits that update those <pathspecs> will be taken into account. It is useful to support monorepos.
+    /// Pathspecs are evaluated relative to the root of the repository.
+    #[clap(short = 'P', long, env = "CONVCO_PATHS", value_delimiter = ',')]
+    pub paths: Vec<String>,
+    /// If no version is found use this version for the first bump
+    #[clap(long, env = "CONVCO_INITIAL_BUMP_VERSION")]
+    pub initial_bump_version: Option<Version>,
+    /// Treat major version zero as stable when calculating the next version.
+    #[clap(long, env = "CONVCO_TREAT_MAJOR_ZERO_AS_STABLE")]
+    pub treat_major_zero_as_stable: bool,
+    /// The changelog to prepend the new section to.
+    #[clap(long, default_value = "CHANGELOG.md", env = "CONVCO_CHANGELOG")]
+    pub changelog: PathBuf,
+    /// Print the new changelog section, release commit message and tag without changing anything.
+    #[clap(short = 'n', long)]
+    pub dry_run: bool,
+    /// GPG-sign the release commit and tag. Otherwise the `commit.gpgSign` and `tag.gpgSign` config of git applies.
+    #[clap(short = 'S', long)]
+    pub sign: bool,
+    /// Only update the changelog, do not create the release commit.
+    #[clap(long)]
+    pub no_commit: bool,
+    /// Do not create the annotated release tag.
+    #[clap(long)]
+    pub no_tag: bool,
+    /// Report the tags that start with the prefix but are not version tags.
+    #[clap(long)]
+    pub verbose: bool,
 }
 
 #[derive(Debug, Parser)]
@@ -102,12 +170,16 @@ pub struct CheckCommand {
     /// Follow only the first parent
     #[clap(long, env = "CONVCO_FIRST_PARENT")]
     pub first_parent: bool,
-    /// Ignore commits created by `git revert` commands
+    /// Ignore revert commits, created by `git revert` or of type `revert`
     #[clap(long, env = "CONVCO_IGNORE_REVERTS")]
     pub ignore_reverts: bool,
     /// Ignore commits whose message matches the given regex pattern
     #[clap(long, env = "CONVCO_IGNORE_MESSAGE_PATTERN")]
     pub ignore_message_pattern: Vec<String>,
+    /// Accept `fixup!`, `squash!` and `amend!` commits whose target commit is in the checked range.
+    /// The target is checked instead.
+    #[clap(long, env = "CONVCO_ALLOW_FIXUPS", conflicts_with = "from_stdin")]
+    pub allow_fixups: bool,
     /// Read a single commit message from stdin
     #[clap(long)]
     pub from_stdin: bool,
@@ -115,6 +187,32 @@ pub struct CheckCommand {
     /// This is similar to `git commit --cleanup=strip`
     #[clap(long, requires("from_stdin"))]
     pub strip: bool,
+    /// Read the refs of a `git push` from stdin, in the format of the pre-push hook,
+    /// and check the commits that are not on the remote yet.
+    #[clap(long, conflicts_with_all(&["rev", "from_stdin"]))]
+    pub pre_push: bool,
+    /// Output format. `json`, `sarif` and `junit` emit one record per checked commit and a summary.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = CheckOutputFormat::Text,
+        env = "CONVCO_OUTPUT_FORMAT"
+    )]
+    pub output_format: CheckOutputFormat,
+    #[clap(flatten)]
+    pub filters: CommitFilterArgs,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum CheckOutputFormat {
+    /// Human readable lines, one per failing commit
+    Text,
+    /// A JSON document with all checked commits and the summary
+    Json,
+    /// A SARIF 2.1.0 log, for code scanning integrations
+    Sarif,
+    /// A JUnit XML report with one test case per commit
+    Junit,
 }
 
 #[cfg(feature = "completions")]
@@ -124,7 +222,7 @@ pub struct CompletionsCommand {
     pub shell: Option<Shells>,
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 pub struct ChangelogCommand {
     /// Prefix used in front of the semantic version.
     #[clap(short, long, default_value = "v", env = "CONVCO_PREFIX")]
@@ -157,6 +255,9 @@ pub struct ChangelogCommand {
     /// Print hidden sections
     #[clap(long, env = "CONVCO_INCLUDE_HIDDEN_SECTIONS")]
     pub include_hidden_sections: bool,
+    /// Leave out revert commits, created by `git revert` or of type `revert`.
+    #[clap(long, env = "CONVCO_IGNORE_REVERTS")]
+    pub ignore_reverts: bool,
     /// Only commits that update those <pathspecs> will be taken into account. It is useful to support monorepos.
     /// Pathspecs are evaluated relative to the root of the repository.
     #[clap(short = 'P', long, env = "CONVCO_PATHS", value_delimiter = ',')]
@@ -173,12 +274,76 @@ pub struct ChangelogCommand {
     #[clap(long, env = "CONVCO_NO_WRAP")]
     pub no_wrap: bool,
     /// Change the title for the unreleased commits.
-    /// If a semantic version is given, the title will be prefixed.
+    /// If a semantic version is given, the title will be prefixed and used as tag to compare with.
     #[clap(short, long, default_value = "Unreleased", env = "CONVCO_UNRELEASED")]
     pub unreleased: String,
     /// Path to write the changelog to.
     #[clap(short, long, default_value = "-", env = "CONVCO_OUTPUT")]
     pub output: PathBuf,
+    /// Only prepend the versions newer than the newest version heading of the existing `--output` file.
+    /// The older sections of the file are kept as they are.
+    #[clap(long, visible_alias = "prepend", env = "CONVCO_INCREMENTAL")]
+    pub incremental: bool,
+    /// Render only the notes of the version of <TAG> with the `release-notes` template, without heading,
+    /// e.g. to paste in the release form of the hosting platform.
+    #[clap(
+        long,
+        value_name = "TAG",
+        conflicts_with_all(&["rev", "max_versions", "incremental", "all"]),
+        env = "CONVCO_RELEASE_NOTES"
+    )]
+    pub release_notes: Option<String>,
+    /// Output format. `json` and `yaml` print the data of every version instead of rendering the template.
+    #[clap(
+        long,
+        value_enum,
+        default_value_t = ChangelogFormat::Markdown,
+        conflicts_with_all(&["incremental", "all"]),
+        env = "CONVCO_FORMAT"
+    )]
+    pub format: ChangelogFormat,
+    /// Write the changelog of every package from the `packages` config.
+    /// Each package uses its own paths, tag prefix and changelog file.
+    #[clap(long, conflicts_with_all(&["prefix", "paths", "output"]))]
+    pub all: bool,
+    /// Report the tags that start with the prefix but are not version tags.
+    #[clap(long)]
+    pub verbose: bool,
+    #[clap(flatten)]
+    pub filters: CommitFilterArgs,
+}
+
+/// Filters of the commits of `check`, `changelog` and `version`.
+#[derive(Debug, Clone, Default, clap::Args)]
+pub struct CommitFilterArgs {
+    /// Only commits committed at or after <since>, a date `YYYY-MM-DD` or an RFC 3339 timestamp.
+    #[clap(long, env = "CONVCO_SINCE")]
+    pub since: Option<String>,
+    /// Only commits committed at or before <until>, a date `YYYY-MM-DD` (included) or an RFC 3339 timestamp.
+    #[clap(long, env = "CONVCO_UNTIL")]
+    pub until: Option<String>,
+    /// Only commits whose author, `Name <email>` after the mailmap, matches one of the regex patterns.
+    #[clap(long = "author", value_name = "PATTERN", env = "CONVCO_AUTHOR")]
+    pub authors: Vec<String>,
+    /// Leave out commits whose author matches this regex pattern, e.g. `dependabot`.
+    #[clap(long, value_name = "PATTERN", env = "CONVCO_EXCLUDE_AUTHOR")]
+    pub exclude_author: Vec<String>,
+    /// Leave out commits with this trailer, e.g. `Skip-Changelog`.
+    #[clap(long, value_name = "TOKEN", env = "CONVCO_EXCLUDE_TRAILER")]
+    pub exclude_trailer: Vec<String>,
+    /// Only commits with one of these trailers.
+    #[clap(long, value_name = "TOKEN", env = "CONVCO_INCLUDE_TRAILER")]
+    pub include_trailer: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChangelogFormat {
+    /// Render the changelog template
+    Markdown,
+    /// A JSON array with one object per version
+    Json,
+    /// A YAML sequence with one entry per version
+    Yaml,
 }
 
 #[derive(Debug, Parser)]
@@ -261,6 +426,10 @@ pub struct CommitCommand {
     /// Interactive mode. Start the wizard if no type and description is given.
     #[clap(long, short, env = "CONVCO_INTERACTIVE")]
     pub interactive: bool,
+    /// Prompt the body and footers in the terminal instead of opening the editor.
+    /// Overrides `useEditor` from the config.
+    #[clap(long, env = "CONVCO_NO_EDITOR")]
+    pub no_editor: bool,
     /// Runs `git add -N <PATH>`.
     /// An entry for the path is placed in the index with no content.
     /// This is useful in combination with --patch.
@@ -279,6 +448,27 @@ pub struct CommitCommand {
     pub extra_args: Vec<String>,
 }
 
+#[derive(Debug, Parser)]
+pub struct HooksCommand {
+    #[clap(subcommand)]
+    pub action: HooksAction,
+}
+
+#[derive(Debug, Parser)]
+pub enum HooksAction {
+    /// Installs the `commit-msg` and `prepare-commit-msg` hooks.
+    /// Existing hooks are kept and run before the convco hook.
+    Install {
+        /// Also install a `pre-push` hook that checks the pushed commits
+        #[clap(long)]
+        pre_push: bool,
+    },
+    /// Removes the hooks installed by convco and restores the hooks they were chained to
+    Uninstall,
+    /// Shows which hooks are installed
+    Status,
+}
+
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct Footer(pub(crate) String, pub(crate) String);
 
@@ -1,12 +1,141 @@
-use convco::Config;
+use std::collections::HashMap;
+
+use convco::{
+    CommitFilters, CommitTrait, Config, ConvcoError, Footer, Repo, RevWalkOptions, TagFormat,
+    VersionFormat, VersionTags, Walk,
+};
+use jiff::{civil::Date, tz::TimeZone, Timestamp};
+use regex::Regex;
+use semver::Version;
+
+use crate::cli::CommitFilterArgs;
 
 mod changelog;
 mod check;
 mod commit;
 mod completions;
 mod config;
+mod hooks;
+mod release;
 mod version;
 
 pub(crate) trait Command {
     fn exec(&self, config: Config) -> anyhow::Result<()>;
 }
+
+/// The version tags of `format`, the highest version first.
+/// With `verbose` the tags with the prefix that are not version tags are reported on stderr.
+pub(crate) fn version_tags<'a, R: Repo<'a>>(
+    repo: &'a R,
+    format: &TagFormat,
+    verbose: bool,
+) -> Result<VersionTags<R::CommitTrait>, ConvcoError> {
+    let tags = repo.version_tags(format)?;
+    if verbose {
+        for (tag, mismatch) in &tags.ignored {
+            eprintln!("ignored tag {tag}: {mismatch}");
+        }
+    }
+    Ok(tags)
+}
+
+/// The name of the tag of `version` in the repository, see [`VersionTags::names`],
+/// or the tag `format` writes if it is not tagged.
+pub(crate) fn tag_name(
+    names: &HashMap<Version, String>,
+    format: &TagFormat,
+    version: &Version,
+) -> String {
+    names
+        .get(version)
+        .cloned()
+        .unwrap_or_else(|| format.tag(version))
+}
+
+/// The version set by a `Release-As` footer, see [`Config::release_as`].
+/// It must be higher than the `last` version.
+pub(crate) fn forced_version(
+    footer: &Footer,
+    versions: &VersionFormat,
+    last: Option<&Version>,
+) -> Result<Version, ConvcoError> {
+    let value = footer.value.trim();
+    let version = versions
+        .parse(value)
+        .ok_or_else(|| ConvcoError::ReleaseAs {
+            footer: footer.key.to_string(),
+            value: value.to_owned(),
+        })?;
+    match last {
+        Some(last) if &version <= last => Err(ConvcoError::ReleaseAsNotForward {
+            footer: footer.key.to_string(),
+            version: versions.format(&version),
+            last: versions.format(last),
+        }),
+        _ => Ok(version),
+    }
+}
+
+/// The version set by the newest `Release-As` footer in the commits of `options`,
+/// with the short id of its commit.
+pub(crate) fn release_as<'a, C: CommitTrait>(
+    commits: &'a dyn Walk<'a, C>,
+    options: RevWalkOptions<'a, C>,
+    config: &Config,
+    versions: &VersionFormat,
+    last: Option<&Version>,
+) -> Result<Option<(String, Version)>, ConvcoError> {
+    for commit in commits.walk(options)?.flatten() {
+        if let Some(footer) = config.release_as(&commit.conventional_commit) {
+            let version = forced_version(footer, versions, last)?;
+            return Ok(Some((commit.commit.short_id(), version)));
+        }
+    }
+    Ok(None)
+}
+
+impl CommitFilterArgs {
+    pub(crate) fn filters(&self) -> Result<CommitFilters, ConvcoError> {
+        Ok(CommitFilters {
+            since: self
+                .since
+                .as_deref()
+                .map(|since| parse_time(since, false))
+                .transpose()?,
+            until: self
+                .until
+                .as_deref()
+                .map(|until| parse_time(until, true))
+                .transpose()?,
+            authors: self
+                .authors
+                .iter()
+                .map(|author| Regex::new(author))
+                .collect::<Result<_, _>>()?,
+            exclude_authors: self
+                .exclude_author
+                .iter()
+                .map(|author| Regex::new(author))
+                .collect::<Result<_, _>>()?,
+            exclude_trailers: self.exclude_trailer.clone(),
+            include_trailers: self.include_trailer.clone(),
+        })
+    }
+}
+
+/// Parses an RFC 3339 timestamp or a local date, the start of the day or the end of the day with `end_of_day`.
+fn parse_time(time: &str, end_of_day: bool) -> Result<Timestamp, ConvcoError> {
+    if let Ok(timestamp) = time.parse::<Timestamp>() {
+        return Ok(timestamp);
+    }
+    let invalid = |_| ConvcoError::InvalidDate(time.to_owned());
+    let day = time
+        .parse::<Date>()
+        .map_err(invalid)?
+        .to_zoned(TimeZone::system())?;
+    let day = match end_of_day {
+        true => day.end_of_day()?,
+        false => day,
+    };
+    Ok(day.timestamp())
+}
@@ -1,17 +1,29 @@
-use std::{borrow::Cow, cmp::Ordering, collections::HashMap, io::Write};
+use std::{
+    borrow::Cow,
+    cmp::Ordering,
+    collections::{HashMap, HashSet},
+    io::Write,
+    path::PathBuf,
+};
 
 use anyhow::Context as _;
 use convco::{
     changelog::{
-        ChangelogWriter, CommitContext, CommitGroup, Context, ContextBase, ContextBuilder, Note,
-        NoteGroup, Reference,
+        parse_co_author, ChangelogWriter, CommitContext, CommitGroup, Context, ContextBase,
+        ContextBuilder, Note, NoteGroup, Person, Reference, ScopeGroup,
     },
-    commit_type_eq, open_repo, CommitParser, CommitTrait, Config, ConvcoError, Footer, FooterKey,
-    MaxMajorsIterExt, MaxMinorsIterExt, MaxPatchesIterExt, Repo, RevWalkOptions,
+    commit_scope_eq, commit_type_eq, open_repo, Commit, CommitParser, CommitTrait, Config,
+    ConvcoError, ConventionalCommit, Footer, FooterKey, GroupBy, MaxMajorsIterExt,
+    MaxMinorsIterExt, MaxPatchesIterExt, Repo, RevWalkIter, RevWalkOptions, Revert, SquashHandling,
+    TagFormat, VersionTags, Walk,
 };
 use semver::Version;
 
-use crate::{cli::ChangelogCommand, Command};
+use crate::{
+    cli::{ChangelogCommand, ChangelogFormat},
+    cmd::{release_as, tag_name, version_tags},
+    Command,
+};
 
 #[derive(Debug, Clone)]
 struct Rev<C> {
@@ -26,25 +38,57 @@ struct Unreleased {
     version: Option<Version>,
 }
 
+/// The commits of `revwalk` with the commit they revert.
+/// A `git revert` commit is listed as a `revert` commit with the subject of the reverted commit.
+fn with_reverts<C: CommitTrait>(revwalk: RevWalkIter<'_, C>) -> Vec<(Commit<C>, Option<Revert>)> {
+    revwalk
+        .filter_map(|commit| {
+            let commit = match commit {
+                Ok(commit) => commit,
+                Err((_, commit)) => {
+                    let revert = Revert::parse(&commit.commit_message().ok()?)?;
+                    Commit {
+                        conventional_commit: ConventionalCommit {
+                            r#type: "revert".into(),
+                            scope: None,
+                            breaking: false,
+                            description: revert.subject,
+                            body: None,
+                            footers: Vec::new(),
+                            references: Vec::new(),
+                        },
+                        commit,
+                    }
+                }
+            };
+            let revert = Revert::parse(&commit.commit.commit_message().ok()?);
+            Some((commit, revert))
+        })
+        .collect()
+}
+
 /// Transforms a range of commits to pass them to the changelog writer.
-struct ChangeLogTransformer<'a, R: Repo<'a>> {
+struct ChangeLogTransformer<'a, 'r, R: Repo<'r>> {
     group_types: Vec<(&'a str, &'a str)>,
     config: &'a Config,
-    revwalk_options: RevWalkOptions<'a, R::CommitTrait>,
+    revwalk_options: RevWalkOptions<'r, R::CommitTrait>,
     unreleased: Unreleased,
-    repo: &'a R,
+    repo: &'r R,
+    commits: &'r dyn Walk<'r, R::CommitTrait>,
+    mailmap: <R::CommitTrait as CommitTrait>::Mailmap,
     context_builder: ContextBuilder<'a>,
-    prefix: &'a str,
+    tags: &'a TagFormat,
 }
 
-impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
+impl<'a, 'r, R: Repo<'r>> ChangeLogTransformer<'a, 'r, R> {
     fn new(
         config: &'a Config,
         include_hidden_sections: bool,
-        repo: &'a R,
-        revwalk_options: RevWalkOptions<'a, R::CommitTrait>,
+        repo: &'r R,
+        commits: &'r dyn Walk<'r, R::CommitTrait>,
+        revwalk_options: RevWalkOptions<'r, R::CommitTrait>,
         unreleased: String,
-        prefix: &'a str,
+        tags: &'a TagFormat,
     ) -> Result<Self, ConvcoError> {
         let group_types = config
             .types
@@ -54,8 +98,9 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
             .collect();
 
         let context_builder = ContextBuilder::new(config)?;
-        let unreleased = match unreleased.parse::<Version>() {
-            Ok(version) => Unreleased {
+        let mailmap = repo.mailmap()?;
+        let unreleased = match tags.versions().parse(&unreleased) {
+            Some(version) => Unreleased {
                 str: unreleased,
                 version: Some(version),
             },
@@ -69,10 +114,12 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
             config,
             group_types,
             repo,
+            commits,
+            mailmap,
             revwalk_options,
             context_builder,
             unreleased,
-            prefix,
+            tags,
         })
     }
 
@@ -109,9 +156,10 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
             ..self.revwalk_options.clone()
         };
 
-        let revwalk = self.repo.revwalk(revwalk_options)?;
-        let mut commits: HashMap<&str, Vec<CommitContext>> = HashMap::new();
+        let revwalk = self.commits.walk(revwalk_options)?;
+        let mut commits: HashMap<Cow<str>, Vec<CommitContext>> = HashMap::new();
         let mut notes: HashMap<String, Vec<Note>> = HashMap::new();
+        let mut contributors = Vec::new();
         let version_date = self
             .repo
             .revision_time(&to_rev.tag, to_rev.commit.as_ref().unwrap())?
@@ -122,57 +170,149 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
             repository,
             ..
         } = self.config;
-        for commit in revwalk.flatten() {
-            let conv_commit = commit.conventional_commit;
-            let footers = conv_commit.footers;
-            self.make_notes(&footers, conv_commit.scope.clone())
-                .into_iter()
-                .for_each(|(key, note)| {
-                    notes.entry(key).or_default().push(note);
+        let commits_with_reverts = with_reverts(revwalk);
+        // A revert cancels the reverted commit of the same version, newest first
+        // so a reverted revert leaves the original commit.
+        let mut cancelled = HashSet::new();
+        // the spelling of scopes that are not configured, the newest commit wins
+        let mut scope_spellings: HashMap<String, String> = HashMap::new();
+        for (commit, revert) in &commits_with_reverts {
+            let id = commit.commit.id();
+            if cancelled.contains(&id) {
+                continue;
+            }
+            let reverted = revert.as_ref().and_then(|revert| {
+                commits_with_reverts
+                    .iter()
+                    .map(|(reverted, _)| reverted.commit.id())
+                    .find(|reverted| revert.reverts(reverted))
+            });
+            if let Some(reverted) = reverted {
+                cancelled.insert(id);
+                cancelled.insert(reverted);
+            }
+        }
+        for (commit, revert) in commits_with_reverts {
+            if cancelled.contains(&commit.commit.id()) {
+                continue;
+            }
+            // a squash commit can have one entry per squashed commit
+            let parser = self.revwalk_options.parser;
+            for conv_commit in parser.expand(commit.conventional_commit) {
+                let footers = conv_commit.footers;
+                let changelog_footers = &self.config.changelog_footers;
+                if changelog_footers.skip(&footers) {
+                    continue;
+                }
+                // aliases of the `scopes` are written with the name of the scope,
+                // other scopes with one spelling per version
+                let scope = conv_commit.scope.map(|scope| {
+                    if let Some(known) = self.config.find_scope(&scope) {
+                        return known.name.clone();
+                    }
+                    match self
+                        .config
+                        .scope_order
+                        .iter()
+                        .find(|ordered| commit_scope_eq(ordered, &scope))
+                    {
+                        Some(ordered) => ordered.clone(),
+                        None => scope_spellings
+                            .entry(scope.to_ascii_lowercase())
+                            .or_insert(scope)
+                            .clone(),
+                    }
                 });
+                self.make_notes(&footers, scope.clone())
+                    .into_iter()
+                    .for_each(|(key, note)| {
+                        notes.entry(key).or_default().push(note);
+                    });
 
-            let hash = commit.commit.id();
-            let date = commit.commit.commit_time()?.date();
-            let scope = conv_commit.scope;
-            let subject = conv_commit.description;
-            let body = conv_commit.body;
-            let short_hash = hash[..7].into();
-            let references = conv_commit
-                .references
-                .into_iter()
-                .map(|r| Reference {
-                    action: r.action,
-                    owner: owner.as_deref().unwrap_or_default(),
-                    repository: repository.as_deref().unwrap_or_default(),
-                    prefix: r.prefix,
-                    issue: r.issue,
-                })
-                .collect();
-            let commit_context = CommitContext {
-                hash,
-                date,
-                scope,
-                subject,
-                body,
-                short_hash,
-                references,
-            };
-            if let Some((_, section)) = self
-                .group_types
-                .iter()
-                .find(|(ty, _)| commit_type_eq(ty, &conv_commit.r#type))
-            {
-                commits.entry(section).or_default().push(commit_context)
+                let hash = commit.commit.id();
+                let date = commit.commit.commit_time()?.date();
+                let subject = changelog_footers
+                    .entry(&footers)
+                    .map_or(conv_commit.description, str::to_owned);
+                let body = conv_commit.body;
+                let short_hash = hash[..7].into();
+                let references = conv_commit
+                    .references
+                    .into_iter()
+                    .map(|r| Reference {
+                        action: r.action,
+                        owner: owner.as_deref().unwrap_or_default(),
+                        repository: repository.as_deref().unwrap_or_default(),
+                        prefix: r.prefix,
+                        issue: r.issue,
+                    })
+                    .collect();
+                let co_authors = footers
+                    .iter()
+                    .filter(|footer| {
+                        footer
+                            .key
+                            .to_string()
+                            .eq_ignore_ascii_case("Co-authored-by")
+                    })
+                    .filter_map(|footer| parse_co_author(&footer.value))
+                    .map(|signature| {
+                        let signature = <R::CommitTrait as CommitTrait>::signature_with_mailmap(
+                            &self.mailmap,
+                            signature,
+                        )?;
+                        self.context_builder.person(signature)
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                let commit_context = CommitContext {
+                    hash,
+                    date,
+                    scope,
+                    subject,
+                    body,
+                    short_hash,
+                    references,
+                    author: self
+                        .context_builder
+                        .person(commit.commit.author_with_mailmap(&self.mailmap)?)?,
+                    committer: self
+                        .context_builder
+                        .person(commit.commit.committer_with_mailmap(&self.mailmap)?)?,
+                    co_authors,
+                };
+                let section: Option<Cow<str>> = match changelog_footers.section(&footers) {
+                    Some(section) => Some(section.to_owned().into()),
+                    None if revert.is_some() => Some(self.config.revert_section.as_str().into()),
+                    None => self
+                        .group_types
+                        .iter()
+                        .find(|(ty, _)| commit_type_eq(ty, &conv_commit.r#type))
+                        .map(|(_, section)| (*section).into()),
+                };
+                if let Some(section) = section {
+                    for person in
+                        std::iter::once(&commit_context.author).chain(&commit_context.co_authors)
+                    {
+                        if !contributors
+                            .iter()
+                            .any(|known: &Person| known.email.eq_ignore_ascii_case(&person.email))
+                        {
+                            contributors.push(person.clone());
+                        }
+                    }
+                    commits.entry(section).or_default().push(commit_context)
+                }
             }
         }
+        contributors.sort_by_cached_key(|person| person.name.to_lowercase());
 
-        let version: Cow<str> = if let Some(version) = &to_rev.version {
-            format!("{}{}", self.prefix, version).into()
+        let version: Cow<str> = if to_rev.version.is_some() {
+            to_rev.tag.clone().into()
         } else if let Some(version_label) = &to_rev.version_label {
             version_label.clone().into()
         } else {
             match &self.unreleased.version {
-                Some(v) => format!("{}{}", self.prefix, v).into(),
+                Some(v) => self.tags.tag(v).into(),
                 None => self.unreleased.str.as_str().into(),
             }
         };
@@ -190,36 +330,141 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
                     .unwrap_or(false));
         let mut commit_groups: Vec<CommitGroup<'_>> = commits
             .into_iter()
-            .map(|(title, commits)| CommitGroup { title, commits })
+            .map(|(title, commits)| CommitGroup {
+                title,
+                commits,
+                scopes: Vec::new(),
+            })
             .collect();
         commit_groups.sort_by(|a, b| self.sort_commit_groups(a, b));
+        let mut scope_groups = Vec::new();
+        match self.config.group_by {
+            GroupBy::Type => {}
+            GroupBy::TypeScope => {
+                for group in &mut commit_groups {
+                    group.scopes = self
+                        .scopes(&group.commits)
+                        .into_iter()
+                        .map(|scope| ScopeGroup {
+                            commits: Self::commits_with_scope(&group.commits, scope.as_deref()),
+                            title: self.scope_title(scope.as_deref()),
+                            scope,
+                            commit_groups: Vec::new(),
+                        })
+                        .collect();
+                }
+            }
+            GroupBy::ScopeType => {
+                let commits: Vec<_> = commit_groups
+                    .iter()
+                    .flat_map(|group| group.commits.iter().cloned())
+                    .collect();
+                scope_groups = self
+                    .scopes(&commits)
+                    .into_iter()
+                    .map(|scope| ScopeGroup {
+                        title: self.scope_title(scope.as_deref()),
+                        commits: Vec::new(),
+                        commit_groups: commit_groups
+                            .iter()
+                            .map(|group| CommitGroup {
+                                title: group.title.clone(),
+                                commits: Self::commits_with_scope(&group.commits, scope.as_deref()),
+                                scopes: Vec::new(),
+                            })
+                            .filter(|group| !group.commits.is_empty())
+                            .collect(),
+                        scope,
+                    })
+                    .collect();
+            }
+        }
         let note_groups: Vec<NoteGroup> = notes
             .into_iter()
             .map(|(title, notes)| NoteGroup { title, notes })
             .collect();
 
+        // An unreleased section with a version will be tagged with that version.
+        let current_tag: Cow<str> = match (&to_rev.version, &to_rev.version_label) {
+            (None, None) => match &self.unreleased.version {
+                Some(v) => self.tags.tag(v).into(),
+                None => to_rev.tag.into(),
+            },
+            _ => to_rev.tag.into(),
+        };
         let context_base = ContextBase {
             version,
             date: Some(version_date),
             is_patch,
             commit_groups,
+            scope_groups,
             note_groups,
+            contributors,
             previous_tag: from_rev.tag,
-            current_tag: to_rev.tag.into(),
+            current_tag,
             host: host.to_owned(),
             owner: owner.to_owned(),
             repository: repository.to_owned(),
+            platform: self.config.hosting_platform(),
             link_compare: self.config.link_compare,
             link_references: self.config.link_references,
         };
         self.context_builder.build(context_base)
     }
 
+    /// The distinct scopes of `commits`, ordered by `scopeOrder` and then alphabetically.
+    /// Commits without a scope (`None`) come last.
+    fn scopes(&self, commits: &[CommitContext<'_>]) -> Vec<Option<String>> {
+        let mut scopes: Vec<Option<String>> = Vec::new();
+        for commit in commits {
+            if !scopes.iter().any(|scope| match (scope, &commit.scope) {
+                (Some(a), Some(b)) => commit_scope_eq(a, b),
+                (a, b) => a == b,
+            }) {
+                scopes.push(commit.scope.clone());
+            }
+        }
+        let position = |scope: &Option<String>| match scope {
+            Some(scope) => (
+                0,
+                self.config
+                    .scope_order
+                    .iter()
+                    .position(|ordered| commit_scope_eq(ordered, scope))
+                    .unwrap_or(usize::MAX),
+                scope.to_ascii_lowercase(),
+            ),
+            None => (1, 0, String::new()),
+        };
+        scopes.sort_by_cached_key(position);
+        scopes
+    }
+
+    fn commits_with_scope<'c>(
+        commits: &[CommitContext<'c>],
+        scope: Option<&str>,
+    ) -> Vec<CommitContext<'c>> {
+        commits
+            .iter()
+            .filter(|commit| match (scope, commit.scope.as_deref()) {
+                (Some(a), Some(b)) => commit_scope_eq(a, b),
+                (a, b) => a == b,
+            })
+            .cloned()
+            .collect()
+    }
+
+    fn scope_title(&self, scope: Option<&str>) -> String {
+        scope
+            .unwrap_or(self.config.unscoped_section.as_str())
+            .to_owned()
+    }
+
     /// Sort commit groups based on how the configuration file contains them.
     /// The index of the first section matching the commit group title will be used as ranking.
     fn sort_commit_groups(&self, a: &CommitGroup<'_>, b: &CommitGroup<'_>) -> Ordering {
-        fn find_pos<'a, R: Repo<'a>>(
-            this: &ChangeLogTransformer<'a, R>,
+        fn find_pos<'a, 'r, R: Repo<'r>>(
+            this: &ChangeLogTransformer<'a, 'r, R>,
             title: &str,
         ) -> Option<usize> {
             this.config
@@ -229,100 +474,161 @@ impl<'a, R: Repo<'a>> ChangeLogTransformer<'a, R> {
                 .find(|(_, x)| x.section == title)
                 .map(|(i, _)| i)
         }
-        let pos_a = find_pos(self, a.title);
-        let pos_b = find_pos(self, b.title);
-        pos_a.cmp(&pos_b)
+        // the sections of `Changelog-Section` footers follow the sections of the types,
+        // by title, and the reverts come last
+        let pos = |title: &str| {
+            find_pos(self, title).unwrap_or(match title == self.config.revert_section {
+                true => self.config.types.len() + 1,
+                false => self.config.types.len(),
+            })
+        };
+        pos(&a.title)
+            .cmp(&pos(&b.title))
+            .then_with(|| a.title.cmp(&b.title))
     }
 }
 
 impl ChangelogCommand {
-    pub(crate) fn write(&self, mut config: Config, stdout: impl Write) -> anyhow::Result<()> {
-        if self.no_links {
-            config.link_references = false;
-            config.link_compare = false;
-        }
-        if self.merges {
-            config.merges = true;
-        }
-        if self.first_parent {
-            config.first_parent = true;
-        }
-        if let Some(line_length) = self.line_length {
-            config.line_length = line_length;
-        }
-        if self.no_wrap {
-            config.wrap_disabled = true;
-        }
+    pub(crate) fn write(&self, config: Config, stdout: impl Write) -> anyhow::Result<()> {
+        let parser = Self::parser(&config);
         let repo = open_repo()?;
+        self.write_in(&repo, &repo, &parser, config, stdout)
+    }
 
-        let rev_str = self.rev.as_str();
-        let (rev_high, rev_high_label, rev_low) = match rev_str.split_once("..") {
+    /// The commit parser of `config`.
+    fn parser(config: &Config) -> CommitParser {
+        CommitParser::builder()
+            .scope_regex(config.scope_regex.clone())
+            .strip_regex(config.strip_regex.clone())
+            .references_regex(format!("({})([0-9]+)", config.issue_prefixes.join("|")))
+            .squash_handling(config.squash_handling)
+            .build()
+    }
+
+    /// The upper end of the range of `--rev` or `--release-notes` with its label,
+    /// and the lower end if there is one.
+    #[allow(clippy::type_complexity)]
+    fn revs<'r, R: Repo<'r>>(
+        &self,
+        repo: &'r R,
+    ) -> Result<(R::CommitTrait, Option<String>, Option<R::CommitTrait>), ConvcoError> {
+        let rev_str = self.release_notes.as_deref().unwrap_or(&self.rev);
+        Ok(match rev_str.split_once("..") {
             None => {
-                let rev_high = Repo::revparse_single(&repo, rev_str)?;
+                let rev_high = Repo::revparse_single(repo, rev_str)?;
                 let label = (rev_str != "HEAD").then(|| rev_str.to_owned());
                 (rev_high, label, None)
             }
             Some(("", rev)) => {
-                let rev_high = Repo::revparse_single(&repo, rev)?;
+                let rev_high = Repo::revparse_single(repo, rev)?;
                 let label = (rev != "HEAD").then(|| rev.to_owned());
                 (rev_high, label, None)
             }
             Some((rev_low, "")) => {
-                let rev_high = Repo::revparse_single(&repo, "HEAD")?;
-                let rev_low = Repo::revparse_single(&repo, rev_low)?;
+                let rev_high = Repo::revparse_single(repo, "HEAD")?;
+                let rev_low = Repo::revparse_single(repo, rev_low)?;
                 (rev_high, None, Some(rev_low))
             }
             Some((rev_low, rev_high)) => {
                 let rev_high_label = (rev_high != "HEAD").then(|| rev_high.to_owned());
-                let rev_high = Repo::revparse_single(&repo, rev_high)?;
-                let rev_low = Repo::revparse_single(&repo, rev_low)?;
+                let rev_high = Repo::revparse_single(repo, rev_high)?;
+                let rev_low = Repo::revparse_single(repo, rev_low)?;
                 (rev_high, rev_high_label, Some(rev_low))
             }
-        };
-        let template = config.template.as_deref();
-        let mut writer = ChangelogWriter::new(template, &config, stdout)?;
-        writer.write_header(config.header.as_str())?;
-        let commit_parser = CommitParser::builder()
-            .scope_regex(config.scope_regex.clone())
-            .strip_regex(config.strip_regex.clone())
-            .references_regex(format!("({})([0-9]+)", config.issue_prefixes.join("|")))
-            .build();
+        })
+    }
+
+    /// Writes the changelog of the commits of `commits`, the whole repository or one package of its history.
+    fn write_in<'r, R: Repo<'r>>(
+        &self,
+        repo: &'r R,
+        commits: &'r dyn Walk<'r, R::CommitTrait>,
+        commit_parser: &'r CommitParser,
+        mut config: Config,
+        stdout: impl Write,
+    ) -> anyhow::Result<()> {
+        if self.no_links {
+            config.link_references = false;
+            config.link_compare = false;
+        }
+        if self.merges {
+            config.merges = true;
+        }
+        if self.first_parent {
+            config.first_parent = true;
+        }
+        if let Some(line_length) = self.line_length {
+            config.line_length = line_length;
+        }
+        if self.no_wrap {
+            config.wrap_disabled = true;
+        }
+        let tags = TagFormat::new(&self.prefix, &config)?;
+        let (rev_high, rev_high_label, rev_low) = self.revs(repo)?;
         let revwalk_options = RevWalkOptions {
             from_rev: rev_low.iter().cloned().collect(),
             to_rev: rev_high.clone(),
             first_parent: config.first_parent,
-            no_merge_commits: !config.merges,
-            no_revert_commits: false, // FIXME no_revert_commits,
+            no_merge_commits: !config.merges && config.squash_handling != SquashHandling::PrTitle,
+            no_revert_commits: self.ignore_reverts,
             paths: self
                 .paths
                 .iter()
                 .map(|p| p.to_string_lossy().to_string())
                 .collect(),
-            parser: &commit_parser,
+            filters: self.filters.filters()?,
+            parser: commit_parser,
         };
-        let transformer = ChangeLogTransformer::new(
-            &config,
-            self.include_hidden_sections,
-            &repo,
-            revwalk_options,
-            self.unreleased.clone(),
-            &self.prefix,
-        )?;
-        let semvers = repo.semver_tags(&self.prefix)?;
+        let VersionTags {
+            versions: semvers,
+            names,
+            ..
+        } = version_tags(repo, &tags, self.verbose)?;
+        let mut contexts = Vec::new();
 
         // Find the highest semver tag reachable from rev_high
-        let tag_high = repo
-            .find_last_version(&rev_high, self.ignore_prereleases, &semvers)
+        let tag_high = commits
+            .last_version(&rev_high, self.ignore_prereleases, &semvers)
             .with_context(|| {
                 format!("Could not find the last version for revision {}", &self.rev)
             })?;
 
         // Find the highest semver tag reachable from rev_low (if set)
         let tag_low = match &rev_low {
-            Some(rev_low) => repo.find_last_version(rev_low, self.ignore_prereleases, &semvers)?,
+            Some(rev_low) => commits.last_version(rev_low, self.ignore_prereleases, &semvers)?,
             None => None,
         };
 
+        // The unreleased commits can set their version with a `Release-As` footer.
+        let unreleased = match tags.versions().parse(&self.unreleased) {
+            Some(_) => self.unreleased.clone(),
+            None => {
+                let options = RevWalkOptions {
+                    from_rev: rev_low
+                        .iter()
+                        .chain(tag_high.iter().map(|(_, commit)| commit))
+                        .cloned()
+                        .collect(),
+                    to_rev: rev_high.clone(),
+                    ..revwalk_options.clone()
+                };
+                let last = tag_high.as_ref().map(|(version, _)| version);
+                match release_as(commits, options, &config, tags.versions(), last)? {
+                    Some((_, version)) => tags.versions().format(&version),
+                    None => self.unreleased.clone(),
+                }
+            }
+        };
+        let transformer = ChangeLogTransformer::new(
+            &config,
+            self.include_hidden_sections,
+            repo,
+            commits,
+            revwalk_options,
+            unreleased,
+            &tags,
+        )?;
+
         match tag_high {
             Some(tag_high) => {
                 // Save the full semvers list for finding the lower boundary tag
@@ -353,7 +659,7 @@ impl ChangelogCommand {
                 let semvers: Vec<Rev<_>> = semver_data
                     .into_iter()
                     .map(|(version, commit)| Rev {
-                        tag: format!("{}{}", self.prefix, version),
+                        tag: tag_name(&names, &tags, &version),
                         commit: Some(commit),
                         version: Some(version),
                         version_label: None,
@@ -377,7 +683,11 @@ impl ChangelogCommand {
 
                 revs.extend(semvers);
 
-                if let Some(max_versions) = self.max_versions {
+                let max_versions = match self.release_notes {
+                    Some(_) => Some(1),
+                    None => self.max_versions,
+                };
+                if let Some(max_versions) = max_versions {
                     revs.truncate(max_versions);
                 }
 
@@ -389,7 +699,7 @@ impl ChangelogCommand {
                             if low_commit.id() == rev_low_commit.id() {
                                 // rev_low is exactly a tag: add it as a versioned boundary
                                 revs.push(Rev {
-                                    tag: format!("{}{}", self.prefix, low_ver),
+                                    tag: tag_name(&names, &tags, low_ver),
                                     commit: Some(low_commit.clone()),
                                     version: Some(low_ver.clone()),
                                     version_label: None,
@@ -427,7 +737,7 @@ impl ChangelogCommand {
                             };
                         if let Some((below_ver, below_commit)) = next_tag_below {
                             revs.push(Rev {
-                                tag: format!("{}{}", self.prefix, below_ver),
+                                tag: tag_name(&names, &tags, &below_ver),
                                 commit: Some(below_commit),
                                 version: Some(below_ver),
                                 version_label: None,
@@ -447,7 +757,7 @@ impl ChangelogCommand {
                 for w in revs.windows(2).map(|w| (w[0].clone(), w[1].clone())) {
                     let context = transformer.transform(w.0, w.1)?;
                     if !self.skip_empty || !context.context.commit_groups.is_empty() {
-                        writer.write_template(&context)?;
+                        contexts.push(context);
                     }
                 }
             }
@@ -470,16 +780,177 @@ impl ChangelogCommand {
                     },
                 )?;
                 if !self.skip_empty || !context.context.commit_groups.is_empty() {
-                    writer.write_template(&context)?;
+                    contexts.push(context);
                 }
             }
         }
+
+        match self.format {
+            ChangelogFormat::Markdown if self.release_notes.is_some() => {
+                let template = config.template.as_deref();
+                let mut writer = ChangelogWriter::new(template, &config, stdout)?;
+                if let Some(context) = contexts.first() {
+                    writer.write_release_notes(context)?;
+                }
+            }
+            ChangelogFormat::Markdown => {
+                let template = config.template.as_deref();
+                let mut writer = ChangelogWriter::new(template, &config, stdout)?;
+                writer.write_header(config.header.as_str())?;
+                for context in &contexts {
+                    writer.write_template(context)?;
+                }
+            }
+            ChangelogFormat::Json => {
+                let mut stdout = stdout;
+                serde_json::to_writer_pretty(&mut stdout, &contexts)?;
+                writeln!(stdout)?;
+            }
+            ChangelogFormat::Yaml => serde_norway::to_writer(stdout, &contexts)?,
+        }
+        Ok(())
+    }
+}
+
+/// Finds the first heading of a changelog with a version tag as title.
+/// The tag of the newest version heading and its offset.
+fn find_version_heading<'c>(changelog: &'c str, tags: &TagFormat) -> Option<(&'c str, usize)> {
+    let mut offset = 0;
+    for line in changelog.split_inclusive('\n') {
+        if line.starts_with('#') {
+            let title = line.trim_start_matches('#').trim_start();
+            let title = title.strip_prefix('[').unwrap_or(title);
+            let end = title
+                .find(|c: char| c.is_whitespace() || c == ']' || c == '(')
+                .unwrap_or(title.len());
+            if tags.version(&title[..end]).is_ok() {
+                return Some((&title[..end], offset));
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// The text of a changelog before its sections, e.g. a title and an introduction.
+/// It ends at the first heading at the level of the version heading at `offset`.
+fn preamble(changelog: &str, offset: usize) -> &str {
+    let level = |line: &str| line.len() - line.trim_start_matches('#').len();
+    let version_level = level(&changelog[offset..]);
+    let mut end = 0;
+    for line in changelog[..offset].split_inclusive('\n') {
+        if level(line) == version_level {
+            break;
+        }
+        end += line.len();
+    }
+    &changelog[..end]
+}
+
+impl ChangelogCommand {
+    /// Prepends the versions newer than the newest version of the existing changelog,
+    /// written by `write`.
+    fn write_incremental(
+        &self,
+        mut config: Config,
+        write: impl FnOnce(&Self, Config, &mut dyn Write) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        if self.output.to_string_lossy() == "-" {
+            Err(ConvcoError::IncrementalWithoutOutput)?;
+        }
+        let existing = match std::fs::read_to_string(&self.output) {
+            Ok(existing) => existing,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => String::new(),
+            Err(e) => Err(e)?,
+        };
+        if existing.is_empty() {
+            return write(self, config, &mut std::fs::File::create(&self.output)?);
+        }
+        let tags = TagFormat::new(&self.prefix, &config)?;
+        let (tag, offset) = find_version_heading(&existing, &tags)
+            .ok_or_else(|| ConvcoError::NoVersionHeading(self.output.display().to_string()))?;
+        let rev = self
+            .rev
+            .rsplit_once("..")
+            .map_or(self.rev.as_str(), |(_, rev)| rev);
+        let command = Self {
+            rev: format!("{tag}..{rev}"),
+            incremental: false,
+            ..self.clone()
+        };
+        let header = std::mem::take(&mut config.header);
+        let mut sections = Vec::new();
+        write(&command, config, &mut sections)?;
+
+        // a hand-edited preamble takes the place of the header, which ends with a single newline
+        let preamble = preamble(&existing, offset).trim_end();
+        let mut out = std::fs::File::create(&self.output)?;
+        if preamble.is_empty() {
+            out.write_all(header.as_bytes())?;
+        } else {
+            out.write_all(preamble.as_bytes())?;
+            out.write_all(b"\n")?;
+        }
+        out.write_all(&sections)?;
+        out.write_all(b"\n")?;
+        out.write_all(&existing.as_bytes()[offset..])?;
+        Ok(())
+    }
+}
+
+impl ChangelogCommand {
+    /// Writes the changelog of every package, from one walk of the history.
+    fn write_all(&self, config: Config) -> anyhow::Result<()> {
+        if config.packages.is_empty() {
+            Err(ConvcoError::NoPackages)?;
+        }
+        let parser = Self::parser(&config);
+        let repo = open_repo()?;
+        let (rev_high, _, rev_low) = self.revs(&repo)?;
+        let tips = std::iter::once(rev_high).chain(rev_low).collect::<Vec<_>>();
+        let paths = config
+            .packages
+            .iter()
+            .map(|package| package.paths.clone())
+            .collect::<Vec<_>>();
+        let history = repo.history(&tips, &paths)?;
+        // the changelogs are relative to the root of the repository, like the paths
+        let root = Repo::workdir(&repo).unwrap_or_default();
+        for (i, package) in config.packages.iter().enumerate() {
+            let command = Self {
+                prefix: package.tag_prefix(),
+                paths: package.paths.iter().map(PathBuf::from).collect(),
+                output: root.join(package.changelog()),
+                all: false,
+                ..self.clone()
+            };
+            let commits = history.package(i);
+            let write = |command: &Self, config, out: &mut dyn Write| {
+                command.write_in(&repo, &commits, &parser, config, out)
+            };
+            if command.incremental {
+                command.write_incremental(config.clone(), write)?;
+            } else {
+                write(
+                    &command,
+                    config.clone(),
+                    &mut std::fs::File::create(&command.output)?,
+                )?;
+            }
+        }
         Ok(())
     }
 }
 
 impl Command for ChangelogCommand {
     fn exec(&self, config: Config) -> anyhow::Result<()> {
+        if self.all {
+            return self.write_all(config);
+        }
+        if self.incremental {
+            return self
+                .write_incremental(config, |command, config, out| command.write(config, out));
+        }
         let out: Box<dyn Write> = match self.output.as_path() {
             p if p.to_string_lossy() == "-" => Box::new(std::io::stdout().lock()),
             p => Box::new(std::fs::File::create(p)?),
@@ -488,3 +959,45 @@ impl Command for ChangelogCommand {
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use convco::{Config, TagFormat};
+
+    use super::{find_version_heading, preamble};
+
+    fn tags(prefix: &str) -> TagFormat {
+        TagFormat::new(prefix, &Config::default()).unwrap()
+    }
+
+    #[test]
+    fn find_version_heading_skips_unreleased_sections() {
+        let changelog = "# Changelog\n\n## Unreleased\n\n* wip\n\n## [v1.2.0](https://example.com) (2024-01-01)\n\n### v1.1.0\n";
+        assert_eq!(
+            find_version_heading(changelog, &tags("v")),
+            Some(("v1.2.0", 35))
+        );
+        assert_eq!(&changelog[35..41], "## [v1");
+    }
+
+    #[test]
+    fn find_version_heading_uses_the_prefix() {
+        let changelog = "# Changelog\n\n### lib@0.3.1 (2024-01-01)\n";
+        assert_eq!(
+            find_version_heading(changelog, &tags("lib@")),
+            Some(("lib@0.3.1", 13))
+        );
+        assert_eq!(find_version_heading(changelog, &tags("v")), None);
+    }
+
+    #[test]
+    fn preamble_ends_before_the_unreleased_sections() {
+        let changelog =
+            "# Changelog\n\nThe *notable* changes.\n\n## Unreleased\n\n* wip\n\n## v1.2.0\n";
+        assert_eq!(
+            preamble(changelog, changelog.find("## v1").unwrap()),
+            "# Changelog\n\nThe *notable* changes.\n\n"
+        );
+        assert_eq!(preamble("## v1.2.0\n", 0), "");
+    }
+}
@@ -1,48 +1,48 @@
+mod report;
+
 use std::{
     borrow::Cow,
     cmp::Ordering,
+    collections::HashSet,
     fmt,
-    io::{stdin, Read},
+    io::{stdin, stdout, BufRead, Read},
 };
 
 use convco::{
-    commit_type_eq, open_repo, strip::Strip, Commit, CommitParser, CommitTrait, Config,
-    ConvcoError, Repo, RevWalkOptions,
+    commit_type_eq, open_repo, strip::Strip, Commit, CommitFilters, CommitParser, CommitTrait,
+    Config, ConvcoError, LintRules, ParseError, Repo, RevWalkOptions, RuleLevel, Scope, Signature,
 };
 use jiff::Zoned;
 use regex::RegexSet;
 
-use crate::{cli::CheckCommand, cmd::Command};
+use self::report::{CheckProblem, Record, Report, Summary};
+use crate::{
+    cli::{CheckCommand, CheckOutputFormat},
+    cmd::Command,
+};
 
-fn print_fail(msg: Cow<str>, short_id: &str, e: impl fmt::Display) -> bool {
-    let first_line = msg.lines().next().unwrap_or("");
-    let short_msg: String = first_line.chars().take(40).collect();
-    if first_line.len() > 40 {
-        println!("FAIL  {}  {}  {}...", short_id, e, short_msg)
-    } else {
-        println!("FAIL  {}  {}  {}", short_id, e, short_msg)
-    }
-    false
+/// A wrong type or scope and the most similar valid value.
+struct ErrorWithSimilaritySuggestions<'a, 'b> {
+    /// `type` or `scope`.
+    kind: &'static str,
+    valid: &'a [String],
+    wrong: &'b str,
 }
 
-struct TypeErrorWithSimilaritySuggestions<'a, 'b> {
-    valid_types: &'a [String],
-    wrong_type: &'b str,
+impl ErrorWithSimilaritySuggestions<'_, '_> {
+    fn suggestion(&self) -> Option<&String> {
+        self.valid
+            .iter()
+            .map(|s| (s, strsim::jaro_winkler(self.wrong, s)))
+            .min_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal))
+            .map(|(suggestion, _)| suggestion)
+    }
 }
 
-impl fmt::Display for TypeErrorWithSimilaritySuggestions<'_, '_> {
+impl fmt::Display for ErrorWithSimilaritySuggestions<'_, '_> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        let Self {
-            valid_types,
-            wrong_type,
-        } = self;
-
-        f.write_fmt(format_args!("wrong type: {wrong_type}"))?;
-        if let Some((suggestion, _)) = valid_types
-            .iter()
-            .map(|s| (s, strsim::jaro_winkler(wrong_type, s)))
-            .min_by(|(_, a), (_, b)| b.partial_cmp(a).unwrap_or(Ordering::Equal))
-        {
+        f.write_fmt(format_args!("wrong {}: {}", self.kind, self.wrong))?;
+        if let Some(suggestion) = self.suggestion() {
             f.write_fmt(format_args!(", did you mean `{suggestion}`"))?;
         }
 
@@ -50,41 +50,109 @@ impl fmt::Display for TypeErrorWithSimilaritySuggestions<'_, '_> {
     }
 }
 
-fn print_wrong_type(
-    msg: Cow<str>,
-    short_id: &str,
-    commit_type: String,
-    valid_types: &[String],
-) -> bool {
-    print_fail(
-        msg,
-        short_id,
-        TypeErrorWithSimilaritySuggestions {
-            wrong_type: &commit_type,
-            valid_types,
-        },
-    )
+/// Stable identifier of an error, used as rule id in the structured reports.
+fn error_rule(e: &ConvcoError) -> &'static str {
+    match e {
+        ConvcoError::Parser(ParseError::NoType) => "no-type",
+        ConvcoError::Parser(ParseError::NoDescription) => "no-description",
+        ConvcoError::Parser(ParseError::EmptyConventionalCommitMessage) => "empty-message",
+        ConvcoError::Parser(ParseError::InvalidFirstLine) => "invalid-first-line",
+        ConvcoError::Parser(ParseError::InvalidScope(_)) => "invalid-scope",
+        _ => "error",
+    }
 }
 
-fn print_check<O: CommitTrait>(
+fn first_line(msg: &str) -> String {
+    msg.lines().next().unwrap_or("").to_owned()
+}
+
+fn check_commit<O: CommitTrait>(
     commit: Result<Commit<O>, (ConvcoError, O)>,
     types: &[String],
-) -> bool {
+    scopes: &[Scope],
+    rules: &LintRules,
+) -> Record {
     match commit {
-        Err((e, o)) => print_fail(o.commit_message().unwrap(), &o.short_id(), e),
+        Err((e, o)) => {
+            let subject = first_line(&o.commit_message().unwrap());
+            Record {
+                sha: o.id(),
+                short_sha: o.short_id(),
+                problems: vec![CheckProblem {
+                    rule: error_rule(&e),
+                    level: RuleLevel::Error,
+                    message: e.to_string(),
+                    line: subject.clone(),
+                    suggestion: None,
+                }],
+                subject,
+            }
+        }
         Ok(Commit {
             conventional_commit,
             commit: oid,
-        }) if !types
-            .iter()
-            .any(|ty| commit_type_eq(ty, &conventional_commit.r#type)) =>
-        {
+        }) => {
             let message = oid
                 .commit_message()
                 .unwrap_or_else(|_| Cow::Owned(conventional_commit.description.clone()));
-            print_wrong_type(message, &oid.short_id(), conventional_commit.r#type, types)
+            let subject = first_line(&message);
+            let mut problems = Vec::new();
+            if !types
+                .iter()
+                .any(|ty| commit_type_eq(ty, &conventional_commit.r#type))
+            {
+                let type_error = ErrorWithSimilaritySuggestions {
+                    kind: "type",
+                    wrong: &conventional_commit.r#type,
+                    valid: types,
+                };
+                problems.push(CheckProblem {
+                    rule: "wrong-type",
+                    level: RuleLevel::Error,
+                    message: type_error.to_string(),
+                    line: subject.clone(),
+                    suggestion: type_error.suggestion().cloned(),
+                });
+            }
+            if let Some(scope) = conventional_commit.scope.as_deref() {
+                if !scopes.is_empty() && !scopes.iter().any(|known| known.is(scope)) {
+                    let names: Vec<String> =
+                        scopes.iter().map(|known| known.name.clone()).collect();
+                    let scope_error = ErrorWithSimilaritySuggestions {
+                        kind: "scope",
+                        wrong: scope,
+                        valid: &names,
+                    };
+                    problems.push(CheckProblem {
+                        rule: "wrong-scope",
+                        level: RuleLevel::Error,
+                        message: scope_error.to_string(),
+                        line: subject.clone(),
+                        suggestion: scope_error.suggestion().cloned(),
+                    });
+                }
+            }
+            problems.extend(
+                rules
+                    .lint(&message, &conventional_commit)
+                    .into_iter()
+                    .map(CheckProblem::from),
+            );
+            Record {
+                sha: oid.id(),
+                short_sha: oid.short_id(),
+                subject,
+                problems,
+            }
         }
-        _ => true,
+    }
+}
+
+/// The git commit, conventional or not.
+fn git_commit<O: CommitTrait>(commit: &Result<Commit<O>, (ConvcoError, O)>) -> &O {
+    match commit {
+        Ok(commit) => &commit.commit,
+        Err((_, commit)) => commit,
     }
 }
 
@@ -92,16 +160,239 @@ fn matches_ignore_pattern<O: CommitTrait>(
     commit: &Result<Commit<O>, (ConvcoError, O)>,
     patterns: &RegexSet,
 ) -> bool {
-    let message = match commit {
-        Ok(commit) => commit.commit.commit_message(),
-        Err((_, commit)) => commit.commit_message(),
-    };
-
-    message
+    git_commit(commit)
+        .commit_message()
         .map(|message| patterns.is_match(message.as_ref()))
         .unwrap_or(false)
 }
 
+/// The target of a `fixup!`, `squash!` or `amend!` subject, as created by `git commit --fixup`:
+/// the subject of the target commit or its (abbreviated) id.
+fn fixup_target(subject: &str) -> Option<&str> {
+    let mut target = subject;
+    while let Some(rest) = ["fixup! ", "squash! ", "amend! "]
+        .iter()
+        .find_map(|prefix| target.strip_prefix(prefix))
+    {
+        target = rest;
+    }
+    (target.len() < subject.len()).then_some(target)
+}
+
+/// Checks a fixup commit: it passes if its target is one of the `commits` of the checked range.
+fn check_fixup<O: CommitTrait>(commit: &O, target: &str, commits: &[(String, String)]) -> Record {
+    let subject = first_line(&commit.commit_message().unwrap_or_default());
+    let is_id = target.len() >= 4 && target.bytes().all(|b| b.is_ascii_hexdigit());
+    let found = commits.iter().any(|(id, subject)| {
+        fixup_target(subject).is_none() && (subject == target || is_id && id.starts_with(target))
+    });
+    let problems = match found {
+        true => Vec::new(),
+        false => vec![CheckProblem {
+            rule: "fixup-target",
+            level: RuleLevel::Error,
+            message: format!("target of the fixup is not in the checked range: {target}"),
+            line: subject.clone(),
+            suggestion: None,
+        }],
+    };
+    Record {
+        sha: commit.id(),
+        short_sha: commit.short_id(),
+        subject,
+        problems,
+    }
+}
+
+/// A commit message without a commit: read from stdin or the body of an `amend!` commit.
+#[derive(Debug, Clone)]
+struct CommitDummy(String);
+impl convco::CommitTrait for CommitDummy {
+    type Mailmap = ();
+    type ObjectId = String;
+
+    fn short_id(&self) -> String {
+        "-".to_owned()
+    }
+
+    fn commit_message(&self) -> Result<Cow<'_, str>, ConvcoError> {
+        Ok(Cow::Borrowed(&self.0))
+    }
+
+    fn id(&self) -> String {
+        self.short_id()
+    }
+
+    fn oid(&self) -> Self::ObjectId {
+        self.short_id()
+    }
+
+    fn commit_time(&self) -> Result<jiff::Zoned, ConvcoError> {
+        Ok(Zoned::now())
+    }
+
+    fn author(&self) -> Result<Signature, ConvcoError> {
+        Ok(Signature {
+            name: String::new(),
+            email: String::new(),
+        })
+    }
+
+    fn committer(&self) -> Result<Signature, ConvcoError> {
+        self.author()
+    }
+
+    fn author_with_mailmap(&self, _: &()) -> Result<Signature, ConvcoError> {
+        self.author()
+    }
+
+    fn committer_with_mailmap(&self, _: &()) -> Result<Signature, ConvcoError> {
+        self.author()
+    }
+
+    fn signature_with_mailmap(_: &(), signature: Signature) -> Result<Signature, ConvcoError> {
+        Ok(signature)
+    }
+}
+
+/// The id and subject of every commit from `from_rev` to `to_rev`, including the commits
+/// left out of the check by filters, ignore patterns and `--number`.
+fn range_subjects<'a, R: Repo<'a>>(
+    repo: &'a R,
+    to_rev: &R::CommitTrait,
+    from_rev: &[R::CommitTrait],
+    parser: &'a CommitParser,
+) -> Result<Vec<(String, String)>, ConvcoError> {
+    let options = RevWalkOptions {
+        from_rev: from_rev.to_vec(),
+        to_rev: to_rev.clone(),
+        first_parent: false,
+        no_merge_commits: false,
+        no_revert_commits: false,
+        paths: vec![],
+        filters: CommitFilters::default(),
+        parser,
+    };
+    Ok(repo
+        .revwalk(options)?
+        .map(|commit| {
+            let commit = git_commit(&commit);
+            let message = commit.commit_message().unwrap_or_default();
+            (CommitTrait::id(commit), first_line(&message))
+        })
+        .collect())
+}
+
+/// The message of an `amend!` commit that replaces the message of its target:
+/// the body after the subject line.
+fn amend_message(message: &str) -> Option<&str> {
+    message.strip_prefix("amend! ")?;
+    let body = message.split_once('\n').map_or("", |(_, body)| body);
+    Some(body.trim_start_matches('\n'))
+}
+
+/// Parses a commit message without a commit.
+fn parse_message(
+    parser: &CommitParser,
+    message: String,
+) -> Result<Commit<CommitDummy>, (ConvcoError, CommitDummy)> {
+    let commit = CommitDummy(message);
+    match parser.parse(&commit.0) {
+        Ok(conventional_commit) => Ok(Commit {
+            conventional_commit,
+            commit,
+        }),
+        Err(e) => Err((e.into(), commit)),
+    }
+}
+
+/// A ref line of the pre-push hook: `<local ref> <local sha> <remote ref> <remote sha>`.
+#[derive(Debug, PartialEq)]
+struct PushedRef<'a> {
+    local_sha: &'a str,
+    /// `None` if the remote ref does not exist yet.
+    remote_sha: Option<&'a str>,
+}
+
+fn is_zero_sha(sha: &str) -> bool {
+    sha.bytes().all(|b| b == b'0')
+}
+
+/// Parses a line of the pre-push protocol. Deleted refs are skipped.
+fn parse_pushed_ref(line: &str) -> Option<PushedRef<'_>> {
+    let mut fields = line.split_whitespace();
+    let (_local_ref, local_sha, _remote_ref, remote_sha) = (
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+        fields.next()?,
+    );
+    if is_zero_sha(local_sha) {
+        return None;
+    }
+    Some(PushedRef {
+        local_sha,
+        remote_sha: (!is_zero_sha(remote_sha)).then_some(remote_sha),
+    })
+}
+
+/// A range to check: the commit to start from and the commits to hide.
+type Range<C> = (C, Vec<C>);
+
+impl CheckCommand {
+    /// The ranges to check.
+    fn ranges<'a, R: Repo<'a>>(
+        &self,
+        repo: &'a R,
+    ) -> Result<Vec<Range<R::CommitTrait>>, ConvcoError> {
+        if self.pre_push {
+            let mut ranges = Vec::new();
+            for line in stdin().lock().lines() {
+                let line = line?;
+                let Some(pushed) = parse_pushed_ref(&line) else {
+                    continue;
+                };
+                let to_rev = repo.revparse_single(pushed.local_sha)?;
+                // a remote sha unknown locally (e.g. a force push) falls back to all remote refs
+                let from_rev = match pushed
+                    .remote_sha
+                    .and_then(|sha| repo.revparse_single(sha).ok())
+                {
+                    Some(remote) => vec![remote],
+                    None => repo.remote_tips()?,
+                };
+                ranges.push((to_rev, from_rev));
+            }
+            return Ok(ranges);
+        }
+        let (to_rev, from_rev) = match self.rev.as_ref() {
+            Some(rev) => match rev.split_once("..") {
+                None => {
+                    let rev = repo.revparse_single(rev)?;
+                    (rev, None)
+                }
+                Some(("", rev)) => {
+                    let rev = repo.revparse_single(rev)?;
+                    (rev, None)
+                }
+                Some((rev_stop, "")) => {
+                    let rev = repo.revparse_single("HEAD")?;
+                    let rev_stop = repo.revparse_single(rev_stop)?;
+                    (rev, Some(rev_stop))
+                }
+                Some((rev_stop, rev)) => {
+                    let rev = repo.revparse_single(rev)?;
+                    let rev_stop = repo.revparse_single(rev_stop)?;
+                    (rev, Some(rev_stop))
+                }
+            },
+
+            None => (repo.revparse_single("HEAD")?, None),
+        };
+        Ok(vec![(to_rev, from_rev.into_iter().collect())])
+    }
+}
+
 impl Command for CheckCommand {
     fn exec(&self, mut config: Config) -> anyhow::Result<()> {
         if self.merges {
@@ -111,8 +402,7 @@ impl Command for CheckCommand {
             config.first_parent = true;
         }
 
-        let mut total = 0;
-        let mut fail = 0;
+        let mut report = Report::new(self.output_format);
 
         let parser = CommitParser::builder()
             .scope_regex(config.scope_regex)
@@ -134,47 +424,17 @@ impl Command for CheckCommand {
             RegexSet::new(ignore_patterns).map_err(|err| anyhow::anyhow!(err.to_string()))?;
 
         if self.from_stdin {
-            #[derive(Debug, Clone)]
-            struct CommitDummy(String);
-            impl convco::CommitTrait for CommitDummy {
-                type ObjectId = String;
-
-                fn short_id(&self) -> String {
-                    "-".to_owned()
-                }
-
-                fn commit_message(&self) -> Result<Cow<'_, str>, ConvcoError> {
-                    Ok(Cow::Borrowed(&self.0))
-                }
-
-                fn id(&self) -> String {
-                    self.short_id()
-                }
-
-                fn oid(&self) -> Self::ObjectId {
-                    self.short_id()
-                }
-
-                fn commit_time(&self) -> Result<jiff::Zoned, ConvcoError> {
-                    Ok(Zoned::now())
-                }
-            }
             let mut stdin = stdin().lock();
             let mut commit_msg = String::new();
             stdin.read_to_string(&mut commit_msg)?;
             if self.strip {
                 commit_msg = commit_msg.strip();
             }
-            let commit = CommitDummy(commit_msg);
-            let result = match parser.parse(&commit.0) {
-                Ok(conventional_commit) => Ok(Commit {
-                    conventional_commit,
-                    commit,
-                }),
-                Err(e) => Err((e.into(), commit)),
-            };
+            let result = parse_message(&parser, commit_msg);
 
-            let is_conventional = print_check(result, &types);
+            let is_conventional =
+                report.add(check_commit(result, &types, &config.scopes, &config.rules));
+            report.write(stdout().lock())?;
             match is_conventional {
                 true => return Ok(()),
                 false => return Err(ConvcoError::Check)?,
@@ -182,59 +442,68 @@ impl Command for CheckCommand {
         }
 
         let repo = open_repo()?;
-        let (to_rev, from_rev) = match self.rev.as_ref() {
-            Some(rev) => match rev.split_once("..") {
-                None => {
-                    let rev = Repo::revparse_single(&repo, rev)?;
-                    (rev, None)
-                }
-                Some(("", rev)) => {
-                    let rev = Repo::revparse_single(&repo, rev)?;
-                    (rev, None)
-                }
-                Some((rev_stop, "")) => {
-                    let rev = Repo::revparse_single(&repo, "HEAD")?;
-                    let rev_stop = Repo::revparse_single(&repo, rev_stop)?;
-                    (rev, Some(rev_stop))
-                }
-                Some((rev_stop, rev)) => {
-                    let rev = Repo::revparse_single(&repo, rev)?;
-                    let rev_stop = Repo::revparse_single(&repo, rev_stop)?;
-                    (rev, Some(rev_stop))
-                }
-            },
-
-            None => (Repo::revparse_single(&repo, "HEAD")?, None),
-        };
-        let options = RevWalkOptions {
-            from_rev: from_rev.into_iter().collect(),
-            to_rev,
-            first_parent: config.first_parent,
-            no_merge_commits: !config.merges,
-            no_revert_commits: self.ignore_reverts,
-            paths: vec![],
-            parser: &parser,
-        };
-        let revwalk = Repo::revwalk(&repo, options)?;
-        let revwalk: Box<dyn Iterator<Item = _>> = if ignore_patterns.is_empty() {
-            Box::new(revwalk)
-        } else {
-            Box::new(revwalk.filter(|commit| !matches_ignore_pattern(commit, &ignore_patterns)))
-        };
-
-        for commit in revwalk.take(self.number.unwrap_or(usize::MAX)) {
-            total += 1;
-            fail += u32::from(!print_check(commit, &types));
+        let mut remaining = self.number.unwrap_or(usize::MAX);
+        let mut seen = HashSet::new();
+        for (to_rev, from_rev) in self.ranges(&repo)? {
+            // the id and subject of every commit of the range, to find the targets of fixups
+            let subjects = match self.allow_fixups {
+                true => range_subjects(&repo, &to_rev, &from_rev, &parser)?,
+                false => Vec::new(),
+            };
+            let options = RevWalkOptions {
+                from_rev,
+                to_rev,
+                first_parent: config.first_parent,
+                no_merge_commits: !config.merges,
+                no_revert_commits: self.ignore_reverts,
+                paths: vec![],
+                filters: self.filters.filters()?,
+                parser: &parser,
+            };
+            let revwalk = Repo::revwalk(&repo, options)?
+                .filter(|commit| {
+                    ignore_patterns.is_empty() || !matches_ignore_pattern(commit, &ignore_patterns)
+                })
+                .filter(|commit| seen.insert(CommitTrait::id(git_commit(commit))));
+            let commits: Vec<_> = revwalk.take(remaining).collect();
+            remaining -= commits.len();
+            for commit in commits {
+                let message = git_commit(&commit)
+                    .commit_message()
+                    .unwrap_or_default()
+                    .into_owned();
+                let subject = first_line(&message);
+                let target = fixup_target(&subject).filter(|_| self.allow_fixups);
+                let record = match target {
+                    Some(target) => {
+                        let mut record = check_fixup(git_commit(&commit), target, &subjects);
+                        if let Some(amend) = amend_message(&message) {
+                            let amend = parse_message(&parser, amend.to_owned());
+                            let checked =
+                                check_commit(amend, &types, &config.scopes, &config.rules);
+                            record.problems.extend(checked.problems);
+                        }
+                        record
+                    }
+                    None => check_commit(commit, &types, &config.scopes, &config.rules),
+                };
+                report.add(record);
+            }
         }
-        if fail == 0 {
-            match total {
-                0 => println!("no commits checked"),
-                1 => println!("no errors in {} commit", total),
-                _ => println!("no errors in {} commits", total),
+        let &Summary { total, failed, .. } = report.summary();
+        if self.output_format == CheckOutputFormat::Text {
+            match (failed, total) {
+                (0, 0) => println!("no commits checked"),
+                (0, 1) => println!("no errors in {} commit", total),
+                (0, _) => println!("no errors in {} commits", total),
+                _ => println!("\n{}/{} failed", failed, total),
             }
+        } else {
+            report.write(stdout().lock())?;
+        }
+        if failed == 0 {
             Ok(())
         } else {
-            println!("\n{}/{} failed", fail, total);
             Err(ConvcoError::Check)?
         }
     }
@@ -242,11 +511,50 @@ impl Command for CheckCommand {
 
 #[cfg(test)]
 mod tests {
+    use super::{fixup_target, parse_pushed_ref, PushedRef};
+
+    #[test]
+    fn fixup_targets() {
+        assert_eq!(fixup_target("fixup! feat: x"), Some("feat: x"));
+        assert_eq!(
+            fixup_target("amend! squash! fix(cli): y"),
+            Some("fix(cli): y")
+        );
+        assert_eq!(fixup_target("squash! 1a2b3c4"), Some("1a2b3c4"));
+        assert_eq!(fixup_target("feat: fixup! x"), None);
+        assert_eq!(fixup_target("fixup!x"), None);
+    }
+
+    #[test]
+    fn pre_push_lines() {
+        let zero = "0".repeat(40);
+        assert_eq!(
+            parse_pushed_ref(&format!("refs/heads/main abc refs/heads/main {zero}")),
+            Some(PushedRef {
+                local_sha: "abc",
+                remote_sha: None
+            })
+        );
+        assert_eq!(
+            parse_pushed_ref("refs/heads/main abc refs/heads/main def"),
+            Some(PushedRef {
+                local_sha: "abc",
+                remote_sha: Some("def")
+            })
+        );
+        assert_eq!(
+            parse_pushed_ref(&format!("(delete) {zero} refs/heads/old def")),
+            None
+        );
+        assert_eq!(parse_pushed_ref(""), None);
+    }
+
     #[test]
     fn test_suggestions() {
-        let output = super::TypeErrorWithSimilaritySuggestions {
-            wrong_type: "tests",
-            valid_types: &[
+        let output = super::ErrorWithSimilaritySuggestions {
+            kind: "type",
+            wrong: "tests",
+            valid: &[
                 "feat", "fix", "build", "chore", "ci", "docs", "style", "refactor", "perf", "test",
             ]
             .map(|s| s.to_string()),
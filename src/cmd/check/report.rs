@@ -0,0 +1,360 @@
+use std::io::{self, Write};
+
+use convco::{Problem, RuleLevel};
+use serde::Serialize;
+use serde_json::json;
+
+use crate::cli::CheckOutputFormat;
+
+/// A reason why a commit did not pass the check, or a warning about it.
+#[derive(Debug, Serialize)]
+pub(crate) struct CheckProblem {
+    /// Stable identifier of the rule, e.g. `invalid-first-line` or `wrong-type`.
+    pub rule: &'static str,
+    pub level: RuleLevel,
+    pub message: String,
+    /// The line of the commit message that caused the problem.
+    pub line: String,
+    /// The most similar valid value, for errors such as `wrong-type`.
+    pub suggestion: Option<String>,
+}
+
+impl From<Problem> for CheckProblem {
+    fn from(problem: Problem) -> Self {
+        Self {
+            rule: problem.rule,
+            level: problem.level,
+            message: problem.message,
+            line: problem.line,
+            suggestion: None,
+        }
+    }
+}
+
+/// One checked commit.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct Record {
+    pub sha: String,
+    pub short_sha: String,
+    pub subject: String,
+    pub problems: Vec<CheckProblem>,
+}
+
+impl Record {
+    fn errors(&self) -> impl Iterator<Item = &CheckProblem> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.level == RuleLevel::Error)
+    }
+
+    fn warnings(&self) -> impl Iterator<Item = &CheckProblem> {
+        self.problems
+            .iter()
+            .filter(|problem| problem.level == RuleLevel::Warning)
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub(crate) struct Summary {
+    pub total: usize,
+    pub failed: usize,
+    pub warnings: usize,
+}
+
+#[derive(Debug, Serialize)]
+struct JsonReport<'a> {
+    commits: &'a [Record],
+    summary: &'a Summary,
+}
+
+/// Collects the results of `convco check`.
+///
+/// Text output is printed as soon as a record is added, the structured formats are written at
+/// the end with [`Report::write`].
+pub(crate) struct Report {
+    format: CheckOutputFormat,
+    records: Vec<Record>,
+    summary: Summary,
+}
+
+impl Report {
+    pub fn new(format: CheckOutputFormat) -> Self {
+        Self {
+            format,
+            records: Vec::new(),
+            summary: Summary {
+                total: 0,
+                failed: 0,
+                warnings: 0,
+            },
+        }
+    }
+
+    pub fn summary(&self) -> &Summary {
+        &self.summary
+    }
+
+    /// Adds a record and returns `true` if the commit passed the check.
+    ///
+    /// A commit passes if it has no problems with level `error`, warnings are only reported.
+    pub fn add(&mut self, record: Record) -> bool {
+        let passed = record.errors().next().is_none();
+        self.summary.total += 1;
+        self.summary.failed += usize::from(!passed);
+        self.summary.warnings += record.warnings().count();
+        if self.format == CheckOutputFormat::Text {
+            for problem in &record.problems {
+                print_problem(&record.subject, &record.short_sha, problem);
+            }
+        } else {
+            self.records.push(record);
+        }
+        passed
+    }
+
+    /// Writes the structured report. Does nothing for the text format.
+    pub fn write(&self, mut w: impl Write) -> io::Result<()> {
+        match self.format {
+            CheckOutputFormat::Text => Ok(()),
+            CheckOutputFormat::Json => {
+                let report = JsonReport {
+                    commits: &self.records,
+                    summary: &self.summary,
+                };
+                serde_json::to_writer_pretty(&mut w, &report)?;
+                writeln!(w)
+            }
+            CheckOutputFormat::Sarif => {
+                serde_json::to_writer_pretty(&mut w, &self.sarif())?;
+                writeln!(w)
+            }
+            CheckOutputFormat::Junit => self.write_junit(w),
+        }
+    }
+
+    fn sarif(&self) -> serde_json::Value {
+        let mut rules: Vec<&str> = self
+            .records
+            .iter()
+            .flat_map(|record| record.problems.iter().map(|problem| problem.rule))
+            .collect();
+        rules.sort_unstable();
+        rules.dedup();
+        let results: Vec<_> = self
+            .records
+            .iter()
+            .flat_map(|record| {
+                record.problems.iter().map(move |problem| {
+                    json!({
+                        "ruleId": problem.rule,
+                        "level": problem.level,
+                        "message": {
+                            "text": format!("{}: {}", record.short_sha, problem.message),
+                        },
+                        "partialFingerprints": {
+                            "commitSha": record.sha,
+                        },
+                        "properties": {
+                            "sha": record.sha,
+                            "subject": record.subject,
+                            "line": problem.line,
+                            "suggestion": problem.suggestion,
+                        },
+                    })
+                })
+            })
+            .collect();
+        json!({
+            "$schema": "https://json.schemastore.org/sarif-2.1.0.json",
+            "version": "2.1.0",
+            "runs": [{
+                "tool": {
+                    "driver": {
+                        "name": "convco",
+                        "informationUri": "https://convco.github.io",
+                        "version": env!("CARGO_PKG_VERSION"),
+                        "rules": rules
+                            .iter()
+                            .map(|rule| json!({ "id": rule }))
+                            .collect::<Vec<_>>(),
+                    },
+                },
+                "results": results,
+                "properties": {
+                    "total": self.summary.total,
+                    "failed": self.summary.failed,
+                    "warnings": self.summary.warnings,
+                },
+            }],
+        })
+    }
+
+    fn write_junit(&self, mut w: impl Write) -> io::Result<()> {
+        let Summary { total, failed, .. } = self.summary;
+        writeln!(w, r#"<?xml version="1.0" encoding="UTF-8"?>"#)?;
+        writeln!(
+            w,
+            r#"<testsuites name="convco" tests="{total}" failures="{failed}">"#
+        )?;
+        writeln!(
+            w,
+            r#"  <testsuite name="convco check" tests="{total}" failures="{failed}">"#
+        )?;
+        for record in &self.records {
+            let name = xml_escape(&format!("{} {}", record.short_sha, record.subject));
+            if record.problems.is_empty() {
+                writeln!(
+                    w,
+                    r#"    <testcase classname="convco.check" name="{name}"/>"#
+                )?;
+                continue;
+            }
+            writeln!(
+                w,
+                r#"    <testcase classname="convco.check" name="{name}">"#
+            )?;
+            for error in record.errors() {
+                writeln!(
+                    w,
+                    r#"      <failure type="{}" message="{}">{}</failure>"#,
+                    error.rule,
+                    xml_escape(&error.message),
+                    xml_escape(&error.line),
+                )?;
+            }
+            for warning in record.warnings() {
+                writeln!(
+                    w,
+                    "      <system-out>warning {}: {}</system-out>",
+                    warning.rule,
+                    xml_escape(&warning.message),
+                )?;
+            }
+            writeln!(w, "    </testcase>")?;
+        }
+        writeln!(w, "  </testsuite>")?;
+        writeln!(w, "</testsuites>")
+    }
+}
+
+fn print_problem(subject: &str, short_id: &str, problem: &CheckProblem) {
+    let status = match problem.level {
+        RuleLevel::Warning => "WARN",
+        _ => "FAIL",
+    };
+    let e = &problem.message;
+    let short_msg: String = subject.chars().take(40).collect();
+    if subject.len() > 40 {
+        println!("{status}  {}  {}  {}...", short_id, e, short_msg)
+    } else {
+        println!("{status}  {}  {}  {}", short_id, e, short_msg)
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    let mut escaped = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            c => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report(format: CheckOutputFormat) -> Report {
+        let mut report = Report::new(format);
+        report.add(Record {
+            sha: "0123456789abcdef".into(),
+            short_sha: "0123456".into(),
+            subject: "feat: valid".into(),
+            problems: vec![],
+        });
+        report.add(Record {
+            sha: "fedcba9876543210".into(),
+            short_sha: "fedcba9".into(),
+            subject: "wip: <draft>".into(),
+            problems: vec![
+                CheckProblem {
+                    rule: "wrong-type",
+                    level: RuleLevel::Error,
+                    message: "wrong type: wip, did you mean `ci`".into(),
+                    line: "wip: <draft>".into(),
+                    suggestion: Some("ci".into()),
+                },
+                CheckProblem {
+                    rule: "subject-full-stop",
+                    level: RuleLevel::Warning,
+                    message: "description must not end with a period".into(),
+                    line: "wip: <draft>".into(),
+                    suggestion: None,
+                },
+            ],
+        });
+        report
+    }
+
+    #[test]
+    fn json_report_contains_records_and_summary() {
+        let mut out = Vec::new();
+        report(CheckOutputFormat::Json).write(&mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(
+            value["summary"],
+            json!({"total": 2, "failed": 1, "warnings": 1})
+        );
+        assert_eq!(value["commits"][0]["problems"], json!([]));
+        assert_eq!(value["commits"][1]["shortSha"], "fedcba9");
+        assert_eq!(value["commits"][1]["problems"][0]["rule"], "wrong-type");
+        assert_eq!(value["commits"][1]["problems"][0]["level"], "error");
+        assert_eq!(value["commits"][1]["problems"][0]["suggestion"], "ci");
+        assert_eq!(value["commits"][1]["problems"][1]["level"], "warning");
+    }
+
+    #[test]
+    fn sarif_report_lists_problems() {
+        let mut out = Vec::new();
+        report(CheckOutputFormat::Sarif).write(&mut out).unwrap();
+        let value: serde_json::Value = serde_json::from_slice(&out).unwrap();
+
+        assert_eq!(value["version"], "2.1.0");
+        let run = &value["runs"][0];
+        assert_eq!(
+            run["tool"]["driver"]["rules"],
+            json!([{"id": "subject-full-stop"}, {"id": "wrong-type"}])
+        );
+        assert_eq!(run["results"].as_array().unwrap().len(), 2);
+        assert_eq!(run["results"][0]["ruleId"], "wrong-type");
+        assert_eq!(run["results"][1]["level"], "warning");
+        assert_eq!(
+            run["properties"],
+            json!({"total": 2, "failed": 1, "warnings": 1})
+        );
+    }
+
+    #[test]
+    fn junit_report_escapes_xml() {
+        let mut out = Vec::new();
+        report(CheckOutputFormat::Junit).write(&mut out).unwrap();
+        let out = String::from_utf8(out).unwrap();
+
+        assert!(out.contains(r#"<testsuite name="convco check" tests="2" failures="1">"#));
+        assert!(out.contains(r#"<testcase classname="convco.check" name="0123456 feat: valid"/>"#));
+        assert!(out.contains(
+            r#"<failure type="wrong-type" message="wrong type: wip, did you mean `ci`">wip: &lt;draft&gt;</failure>"#
+        ));
+        assert!(out.contains(
+            "<system-out>warning subject-full-stop: description must not end with a period</system-out>"
+        ));
+    }
+}
@@ -6,8 +6,8 @@ use std::{
 };
 
 use convco::{
-    commit_scope_eq, open_repo, strip::Strip, CommitParser, Config, ConvcoError, ParseError, Repo,
-    RevWalkOptions, Type,
+    commit_scope_eq, open_repo, strip::Strip, CommitFilters, CommitParser, Config, ConvcoError,
+    ConventionalCommit, LintRules, ParseError, Repo, RevWalkOptions, RuleLevel, Scope, Type,
 };
 use dialoguer::{BasicHistory, Completion, History};
 use handlebars::{no_escape, Handlebars};
@@ -94,6 +94,64 @@ fn read_scope(
     Ok(result)
 }
 
+/// Selects one of the `scopes` of the configuration, or no scope.
+fn select_scope(
+    theme: &impl dialoguer::theme::Theme,
+    selected: &str,
+    scopes: &[Scope],
+) -> Result<String, ConvcoError> {
+    let items: Vec<String> = std::iter::once("(no scope)".to_owned())
+        .chain(scopes.iter().map(|scope| match &scope.description {
+            Some(description) => format!("{}: {description}", scope.name),
+            None => scope.name.clone(),
+        }))
+        .collect();
+    let index = dialoguer::FuzzySelect::with_theme(theme)
+        .with_prompt("scope")
+        .items(&items)
+        .default(
+            scopes
+                .iter()
+                .position(|scope| scope.is(selected))
+                .map_or(0, |i| i + 1),
+        )
+        .interact()?;
+    Ok(match index {
+        0 => String::new(),
+        i => scopes[i - 1].name.clone(),
+    })
+}
+
+/// The paths of the staged files.
+fn staged_paths() -> Vec<String> {
+    process::Command::new("git")
+        .args(["diff", "--cached", "--name-only"])
+        .output()
+        .ok()
+        .filter(|output| output.status.success())
+        .map(|output| {
+            String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+/// The scope whose `paths` match the most staged files.
+fn staged_scope<'a>(scopes: &'a [Scope], paths: &[String]) -> Option<&'a Scope> {
+    scopes
+        .iter()
+        .map(|scope| {
+            let count = paths.iter().filter(|path| scope.matches_path(path)).count();
+            (scope, count)
+        })
+        .filter(|(_, count)| *count > 0)
+        .rev()
+        .max_by_key(|(_, count)| *count)
+        .map(|(scope, _)| scope)
+}
+
 struct ScopeCompletion {
     scopes: Vec<String>,
     state: Mutex<Option<ScopeCompletionState>>,
@@ -257,28 +315,52 @@ fn edit_message(msg: &str) -> Result<String, ConvcoError> {
         .strip())
 }
 
-fn edit_loop(msg: &str, parser: &CommitParser, types: &[String]) -> Result<String, ConvcoError> {
+/// Prints the type error and the lint problems of `commit`.
+/// Returns `true` if the message has errors.
+fn print_problems(
+    msg: &str,
+    commit: &ConventionalCommit,
+    types: &[String],
+    rules: &LintRules,
+) -> bool {
+    if !types.contains(&commit.r#type) {
+        eprintln!(
+            "ParseError: {}",
+            ConvcoError::Type {
+                wrong_type: commit.r#type.to_string(),
+            }
+        );
+        return true;
+    }
+    let problems = rules.lint(msg, commit);
+    for problem in &problems {
+        eprintln!("{} [{}]: {}", problem.level, problem.rule, problem.message);
+    }
+    problems
+        .iter()
+        .any(|problem| problem.level == RuleLevel::Error)
+}
+
+fn edit_loop(
+    msg: &str,
+    parser: &CommitParser,
+    types: &[String],
+    rules: &LintRules,
+) -> Result<String, ConvcoError> {
     let mut edit_msg = msg.to_owned();
     loop {
         edit_msg = edit_message(&edit_msg)?;
         match parser.parse(&edit_msg) {
             Ok(commit) => {
-                if !types.contains(&commit.r#type) {
-                    eprintln!(
-                        "ParseError: {}",
-                        ConvcoError::Type {
-                            wrong_type: commit.r#type.to_string(),
-                        }
-                    );
-                    if !dialoguer::Confirm::new()
-                        .with_prompt("Continue?")
-                        .interact()?
-                    {
-                        break Err(ConvcoError::CancelledByUser);
-                    }
-                } else {
+                if !print_problems(&edit_msg, &commit, types, rules) {
                     break Ok(edit_msg);
                 }
+                if !dialoguer::Confirm::new()
+                    .with_prompt("Continue?")
+                    .interact()?
+                {
+                    break Err(ConvcoError::CancelledByUser);
+                }
             }
             Err(ParseError::EmptyConventionalCommitMessage) => {
                 break Err(ConvcoError::CancelledByUser);
@@ -296,6 +378,82 @@ fn edit_loop(msg: &str, parser: &CommitParser, types: &[String]) -> Result<Strin
     }
 }
 
+/// Reads the body line by line until an empty line. `body` is kept in front of the new lines.
+fn read_body(theme: &impl dialoguer::theme::Theme, body: &str) -> Result<String, ConvcoError> {
+    let mut body = body.to_owned();
+    loop {
+        let line = read_single_line(theme, "body (empty line to finish)", "")?;
+        if line.is_empty() {
+            break Ok(body);
+        }
+        if !body.is_empty() {
+            body.push('\n');
+        }
+        body.push_str(&line);
+    }
+}
+
+/// Reads footers as token and value until an empty token.
+fn read_footers(
+    theme: &impl dialoguer::theme::Theme,
+    parser: &CommitParser,
+) -> Result<Vec<String>, ConvcoError> {
+    let mut footers = Vec::new();
+    loop {
+        let token: String = dialoguer::Input::with_theme(theme)
+            .with_prompt("footer token (e.g. Reviewed-by, empty to finish)")
+            .allow_empty(true)
+            .validate_with(|token: &String| {
+                if token.is_empty() || parser.is_footer(&format!("{token}: value")) {
+                    Ok(())
+                } else {
+                    Err("a token is a word or words separated by `-`")
+                }
+            })
+            .interact_text()?;
+        if token.is_empty() {
+            break Ok(footers);
+        }
+        let value: String = dialoguer::Input::with_theme(theme)
+            .with_prompt(token.as_str())
+            .validate_with(|value: &String| {
+                if parser.is_footer(&format!("{token}: {value}")) {
+                    Ok(())
+                } else {
+                    Err("the value must be a single line")
+                }
+            })
+            .interact_text()?;
+        footers.push(format!("{token}: {value}"));
+    }
+}
+
+/// Shows the final message and asks for confirmation, instead of opening the editor.
+fn confirm_message(
+    theme: &impl dialoguer::theme::Theme,
+    msg: &str,
+    parser: &CommitParser,
+    types: &[String],
+    rules: &LintRules,
+) -> Result<String, ConvcoError> {
+    let commit = parser.parse(msg)?;
+    eprintln!("\n{msg}");
+    let has_errors = print_problems(msg, &commit, types, rules);
+    let confirmed = dialoguer::Confirm::with_theme(theme)
+        .with_prompt(if has_errors {
+            "The message has errors, commit anyway?"
+        } else {
+            "Commit with this message?"
+        })
+        .default(!has_errors)
+        .interact()?;
+    if confirmed {
+        Ok(msg.to_owned())
+    } else {
+        Err(ConvcoError::CancelledByUser)
+    }
+}
+
 #[derive(Serialize)]
 struct Dialog {
     r#type: String,
@@ -366,14 +524,27 @@ impl Dialog {
             })
             .unwrap();
             self.r#type = Self::select_type(theme, self.r#type.as_str(), types)?;
-            self.scope = read_scope(theme, self.scope.as_str(), scope_regex, scopes)?;
+            self.scope = if config.scopes.is_empty() {
+                read_scope(theme, self.scope.as_str(), scope_regex, scopes)?
+            } else {
+                if self.scope.is_empty() {
+                    if let Some(scope) = staged_scope(&config.scopes, &staged_paths()) {
+                        self.scope = scope.name.clone();
+                    }
+                }
+                select_scope(theme, self.scope.as_str(), &config.scopes)?
+            };
             self.description = read_description(
                 theme,
                 self.description.clone(),
                 config.description.length.min.unwrap_or(0),
                 config.description.length.max.unwrap_or(usize::MAX),
             )?;
-            self.body = format!("{}\n{}", self.body, BODY_MSG);
+            if config.use_editor {
+                self.body = format!("{}\n{}", self.body, BODY_MSG);
+            } else {
+                self.body = read_body(theme, &self.body)?;
+            }
             self.breaking_change = read_single_line(
                 theme,
                 "optional BREAKING change",
@@ -389,17 +560,28 @@ impl Dialog {
             .filter(|s| !s.is_empty())
             .map(|s| s.to_owned())
             .collect();
+            if !config.use_editor {
+                self.footers.extend(read_footers(theme, &parser)?);
+            }
             // finally make message
             let msg = handlebars
                 .render("commit-message", self)
                 .map_err(Box::new)?;
-            edit_loop(&msg, &parser, &config_types_to_conventional(types))
+            let types = config_types_to_conventional(types);
+            if config.use_editor {
+                edit_loop(&msg, &parser, &types, &config.rules)
+            } else {
+                confirm_message(theme, &msg.strip(), &parser, &types, &config.rules)
+            }
         }
     }
 }
 
 impl Command for CommitCommand {
-    fn exec(&self, config: Config) -> anyhow::Result<()> {
+    fn exec(&self, mut config: Config) -> anyhow::Result<()> {
+        if self.no_editor {
+            config.use_editor = false;
+        }
         let commit_editmsg = match &self.commit_msg_path {
             Some(path) => path.to_owned(),
             None => get_default_commit_msg_path()?,
@@ -422,7 +604,7 @@ impl Command for CommitCommand {
         if let Ok(ref msg) = std::fs::read_to_string(commit_editmsg_path) {
             if parser.parse(msg).is_ok() {
                 if is_git_editor {
-                    let msg = edit_loop(msg, &parser, &types)?;
+                    let msg = edit_loop(msg, &parser, &types, &config.rules)?;
                     std::fs::write(commit_editmsg_path, msg)?;
                     return Ok(());
                 }
@@ -439,7 +621,7 @@ impl Command for CommitCommand {
                             return Ok(());
                         }
                         "e" | "edit" => {
-                            let msg = edit_loop(msg, &parser, &types)?;
+                            let msg = edit_loop(msg, &parser, &types, &config.rules)?;
                             self.commit_msg_and_remove_file(&msg, commit_editmsg_path)?;
                         }
                         "r" | "reject" => break,
@@ -563,16 +745,18 @@ where
         no_merge_commits: false,
         no_revert_commits: true,
         paths: Vec::new(),
+        filters: CommitFilters::default(),
         parser,
     };
 
     let mut seen = HashSet::new();
     let mut scopes = Vec::new();
 
-    for commit in Repo::revwalk(repo, options)?.take(scope_history_limit) {
-        if let Ok(commit) = commit {
-            push_scope(&mut scopes, &mut seen, commit.conventional_commit.scope);
-        }
+    for commit in Repo::revwalk(repo, options)?
+        .take(scope_history_limit)
+        .flatten()
+    {
+        push_scope(&mut scopes, &mut seen, commit.conventional_commit.scope);
     }
 
     Ok(scopes)
@@ -595,6 +779,37 @@ fn get_default_commit_msg_path() -> Result<PathBuf, ConvcoError> {
 mod tests {
     use super::*;
 
+    #[test]
+    fn staged_scope_matches_the_most_files() {
+        let scope = |name: &str, paths: &[&str]| Scope {
+            name: name.into(),
+            description: None,
+            aliases: vec![],
+            paths: paths.iter().map(|&path| path.into()).collect(),
+        };
+        let scopes = [
+            scope("cli", &["src/cmd"]),
+            scope("docs", &["*.md", "docs"]),
+            scope("readme", &["README.md"]),
+        ];
+        let paths = |paths: &[&str]| {
+            paths
+                .iter()
+                .map(|&path| path.to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let staged = staged_scope(
+            &scopes,
+            &paths(&["src/cmd/check.rs", "README.md", "docs/a.md"]),
+        );
+        assert_eq!(staged.map(|scope| scope.name.as_str()), Some("docs"));
+        // the first scope wins a tie
+        let staged = staged_scope(&scopes, &paths(&["README.md"]));
+        assert_eq!(staged.map(|scope| scope.name.as_str()), Some("docs"));
+        assert_eq!(staged_scope(&scopes, &paths(&["Cargo.toml"])), None);
+    }
+
     #[test]
     fn scope_completion_returns_unique_prefix_match() {
         let completion = ScopeCompletion::new(&["commit".into(), "changelog".into()]);
@@ -0,0 +1,177 @@
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+    process,
+};
+
+use convco::{Config, ConvcoError};
+
+use crate::{
+    cli::{HooksAction, HooksCommand},
+    cmd::Command,
+};
+
+/// Marks a hook as written by convco, so it can be updated and removed safely.
+const MARKER: &str = "# convco: managed hook";
+
+/// Suffix of a hook that existed before convco was installed. The convco hook runs it first.
+const CHAINED: &str = ".convco-chained";
+
+const HOOKS: [&str; 3] = ["commit-msg", "prepare-commit-msg", "pre-push"];
+
+const RUN_CHAINED: &str = r#"if [ -x "$0.convco-chained" ]; then
+    "$0.convco-chained" "$@" || exit $?
+fi
+"#;
+
+const COMMIT_MSG: &str = r#"exec convco check --from-stdin --strip < "$1"
+"#;
+
+// Only starts the wizard for a plain `git commit`, not for `-m`, `-F`, merges or amends.
+const PREPARE_COMMIT_MSG: &str = r#"if [ -z "$2" ] && [ -t 1 ] && (exec < /dev/tty) 2>/dev/null; then
+    exec < /dev/tty
+    convco commit --interactive "$1"
+fi
+"#;
+
+const PRE_PUSH: &str = r#"input=$(cat)
+if [ -x "$0.convco-chained" ]; then
+    printf '%s\n' "$input" | "$0.convco-chained" "$@" || exit $?
+fi
+printf '%s\n' "$input" | exec convco check --pre-push
+"#;
+
+fn script(hook: &str) -> String {
+    let body = match hook {
+        "commit-msg" => [RUN_CHAINED, COMMIT_MSG].concat(),
+        "prepare-commit-msg" => [RUN_CHAINED, PREPARE_COMMIT_MSG].concat(),
+        _ => PRE_PUSH.to_owned(),
+    };
+    format!("#!/bin/sh\n{MARKER}, update with `convco hooks install`\n{body}")
+}
+
+/// The hooks directory, `core.hooksPath` if it is set.
+fn hooks_dir() -> Result<PathBuf, ConvcoError> {
+    let output = process::Command::new("git")
+        .args(["rev-parse", "--git-path", "hooks"])
+        .output()?;
+    if !output.status.success() {
+        return Err(ConvcoError::GitFailed {
+            command: "rev-parse",
+            status: output.status,
+        });
+    }
+    Ok(PathBuf::from(
+        String::from_utf8_lossy(&output.stdout).trim_end(),
+    ))
+}
+
+fn is_managed(path: &Path) -> bool {
+    fs::read_to_string(path).is_ok_and(|content| content.contains(MARKER))
+}
+
+fn chained(path: &Path) -> PathBuf {
+    let mut chained = path.as_os_str().to_owned();
+    chained.push(CHAINED);
+    chained.into()
+}
+
+#[cfg(unix)]
+fn make_executable(path: &Path) -> io::Result<()> {
+    use std::os::unix::fs::PermissionsExt;
+    fs::set_permissions(path, fs::Permissions::from_mode(0o755))
+}
+
+#[cfg(not(unix))]
+fn make_executable(_path: &Path) -> io::Result<()> {
+    Ok(())
+}
+
+/// Writes the hook. An existing hook that is not managed by convco is moved aside and chained.
+fn install(dir: &Path, hook: &str) -> io::Result<()> {
+    let path = dir.join(hook);
+    if path.exists() && !is_managed(&path) {
+        let chained = chained(&path);
+        if chained.exists() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                format!(
+                    "{} is not managed by convco and {} already exists",
+                    path.display(),
+                    chained.display()
+                ),
+            ));
+        }
+        fs::rename(&path, &chained)?;
+        println!("{hook}: moved the existing hook to {}", chained.display());
+    }
+    fs::write(&path, script(hook))?;
+    make_executable(&path)?;
+    println!("{hook}: installed");
+    Ok(())
+}
+
+/// Removes the hook if it is managed by convco and restores the chained hook.
+fn uninstall(dir: &Path, hook: &str) -> io::Result<()> {
+    let path = dir.join(hook);
+    if !is_managed(&path) {
+        return Ok(());
+    }
+    fs::remove_file(&path)?;
+    let chained = chained(&path);
+    if chained.exists() {
+        fs::rename(&chained, &path)?;
+        println!("{hook}: removed, restored the previous hook");
+    } else {
+        println!("{hook}: removed");
+    }
+    Ok(())
+}
+
+fn status(dir: &Path, hook: &str) -> String {
+    let path = dir.join(hook);
+    let status = if is_managed(&path) {
+        if fs::read_to_string(&path).is_ok_and(|content| content == script(hook)) {
+            "installed"
+        } else {
+            "outdated, run `convco hooks install`"
+        }
+    } else if path.exists() {
+        "not managed by convco"
+    } else {
+        "not installed"
+    };
+    if chained(&path).exists() {
+        format!("{hook}: {status}, runs {hook}{CHAINED} first")
+    } else {
+        format!("{hook}: {status}")
+    }
+}
+
+impl Command for HooksCommand {
+    fn exec(&self, _config: Config) -> anyhow::Result<()> {
+        let dir = hooks_dir()?;
+        match self.action {
+            HooksAction::Install { pre_push } => {
+                fs::create_dir_all(&dir)?;
+                for hook in HOOKS {
+                    if hook != "pre-push" || pre_push {
+                        install(&dir, hook)?;
+                    }
+                }
+            }
+            HooksAction::Uninstall => {
+                for hook in HOOKS {
+                    uninstall(&dir, hook)?;
+                }
+            }
+            HooksAction::Status => {
+                println!("hooks directory: {}", dir.display());
+                for hook in HOOKS {
+                    println!("{}", status(&dir, hook));
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,195 @@
+use std::{fs, io, path::PathBuf, process};
+
+use clap::{CommandFactory, FromArgMatches};
+use convco::{
+    changelog::{ContextBase, ContextBuilder},
+    open_repo, CommitTrait, Config, ConvcoError, Repo, TagFormat,
+};
+use jiff::Zoned;
+
+use crate::{
+    cli::{ChangelogCommand, ReleaseCommand, VersionCommand},
+    cmd::{tag_name, version_tags, Command},
+};
+
+fn git(command: &'static str, args: &[&str]) -> Result<(), ConvcoError> {
+    let status = process::Command::new("git")
+        .arg(command)
+        .args(args)
+        .status()?;
+    if status.success() {
+        Ok(())
+    } else {
+        Err(ConvcoError::GitFailed { command, status })
+    }
+}
+
+/// Inserts `section` right after the `header` of an existing changelog.
+fn prepend_section(changelog: &str, header: &str, section: &str) -> String {
+    let rest = changelog.strip_prefix(header).unwrap_or(changelog);
+    format!("{header}{section}{rest}")
+}
+
+/// Parses `args` with the defaults of the command, without the `CONVCO_*` environment variables
+/// that are meant for the command when it runs on its own.
+fn parse_without_env<T: CommandFactory + FromArgMatches>(args: &[&str]) -> Result<T, clap::Error> {
+    let matches = T::command()
+        .mut_args(|arg| arg.env(None))
+        .try_get_matches_from(args)?;
+    T::from_arg_matches(&matches)
+}
+
+impl ReleaseCommand {
+    fn version_command(&self) -> Result<VersionCommand, clap::Error> {
+        let mut command: VersionCommand = parse_without_env(&["version", "--bump"])?;
+        command.prefix = self.prefix.clone();
+        command.major = self.major;
+        command.minor = self.minor;
+        command.patch = self.patch;
+        command.prerelease = self.prerelease.clone();
+        command.paths = self.paths.clone();
+        command.initial_bump_version = self.initial_bump_version.clone();
+        command.treat_major_zero_as_stable = self.treat_major_zero_as_stable;
+        Ok(command)
+    }
+
+    /// Renders the changelog section of the unreleased commits, titled with `version`.
+    fn changelog_section(&self, config: &Config, version: &str) -> anyhow::Result<String> {
+        let mut command: ChangelogCommand = parse_without_env(&["changelog"])?;
+        command.prefix = self.prefix.clone();
+        command.paths = self.paths.iter().map(PathBuf::from).collect();
+        command.unreleased = version.to_owned();
+        command.max_versions = Some(1);
+        let mut config = config.clone();
+        config.header = String::new();
+        let mut section = Vec::new();
+        command.write(config, &mut section)?;
+        Ok(String::from_utf8(section)?)
+    }
+}
+
+impl Command for ReleaseCommand {
+    fn exec(&self, config: Config) -> anyhow::Result<()> {
+        let repo = open_repo()?;
+        let head = Repo::revparse_single(&repo, "HEAD")?;
+        let tags = TagFormat::new(&self.prefix, &config)?;
+        let version_tags = version_tags(&repo, &tags, self.verbose)?;
+        let previous_tag = match repo.find_last_version(&head, false, &version_tags.versions)? {
+            Some((version, commit)) => {
+                let tag = tag_name(&version_tags.names, &tags, &version);
+                if CommitTrait::id(&commit) == CommitTrait::id(&head) {
+                    return Err(ConvcoError::NothingToRelease(tag).into());
+                }
+                tag
+            }
+            None => String::new(),
+        };
+
+        let version = self.version_command()?.version(&config)?;
+        let tag = tags.new_tag(&version)?;
+        let is_patch = version.patch != 0;
+        let version = tags.versions().format(&version);
+        if Repo::revparse_single(&repo, &tag).is_ok() {
+            Err(ConvcoError::TagExists(tag.clone()))?;
+        }
+        let section = self.changelog_section(&config, &version)?;
+        let bumped = VersionCommand::write_bump_files(&config.bump_files, &version, true)?;
+
+        let context_builder = ContextBuilder::new(&config)?;
+        let message = context_builder
+            .build(ContextBase {
+                version: tag.as_str().into(),
+                date: Some(Zoned::now().date()),
+                is_patch,
+                commit_groups: Vec::new(),
+                scope_groups: Vec::new(),
+                note_groups: Vec::new(),
+                contributors: Vec::new(),
+                previous_tag,
+                current_tag: tag.as_str().into(),
+                host: config.host.clone(),
+                owner: config.owner.clone(),
+                repository: config.repository.clone(),
+                platform: config.hosting_platform(),
+                link_compare: config.link_compare,
+                link_references: config.link_references,
+            })?
+            .release_commit_message_format;
+
+        if self.dry_run {
+            println!("{}", section.trim());
+            println!();
+            let root = Repo::workdir(&repo).unwrap_or_default();
+            for path in &bumped {
+                println!(
+                    "bump: {}",
+                    path.strip_prefix(&root).unwrap_or(path).display()
+                );
+            }
+            if !self.no_commit {
+                println!("commit: {message}");
+            }
+            if !self.no_tag {
+                println!("tag: {tag}");
+            }
+            return Ok(());
+        }
+
+        let changelog = match fs::read_to_string(&self.changelog) {
+            Ok(changelog) => changelog,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(e) => Err(e)?,
+        };
+        fs::write(
+            &self.changelog,
+            prepend_section(&changelog, &config.header, &section),
+        )?;
+        let bumped = VersionCommand::write_bump_files(&config.bump_files, &version, false)?;
+
+        if !self.no_commit {
+            let mut paths = vec![self.changelog.to_string_lossy()];
+            paths.extend(bumped.iter().map(|path| path.to_string_lossy()));
+            let mut pathspec = vec!["--"];
+            pathspec.extend(paths.iter().map(AsRef::as_ref));
+            git("add", &pathspec)?;
+            // only the changelog and the bump files, not what else is staged
+            let mut args = vec!["-m", &message];
+            if self.sign {
+                args.push("-S");
+            }
+            args.extend(&pathspec);
+            git("commit", &args)?;
+        }
+        if !self.no_tag {
+            let mut args = vec![tag.as_str(), "-m", &message];
+            if self.sign {
+                args.push("-s");
+            }
+            git("tag", &args)?;
+        }
+        println!("{tag}");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::prepend_section;
+
+    #[test]
+    fn prepend_section_keeps_header_on_top() {
+        let changelog = "# Changelog\n\n## v1.0.0\n\n* first\n";
+        assert_eq!(
+            prepend_section(changelog, "# Changelog\n", "\n## v1.1.0\n\n* second\n"),
+            "# Changelog\n\n## v1.1.0\n\n* second\n\n## v1.0.0\n\n* first\n"
+        );
+    }
+
+    #[test]
+    fn prepend_section_adds_header_to_new_changelog() {
+        assert_eq!(
+            prepend_section("", "# Changelog\n", "\n## v0.1.0\n"),
+            "# Changelog\n\n## v0.1.0\n"
+        );
+    }
+}
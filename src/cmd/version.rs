@@ -1,12 +1,18 @@
-use std::fmt;
+mod bump_files;
+
+use std::{collections::HashSet, fmt, path::PathBuf};
 
 use convco::{
-    commit_type_eq, open_repo, CommitParser, CommitTrait, Config, ConvcoError, Increment, Repo,
-    RevWalkOptions, Type,
+    commit_type_eq, open_repo, BumpFile, CommitParser, CommitTrait, Config, ConvcoError, Increment,
+    Repo, RevWalkOptions, TagFormat, VersionFormat, Walk,
 };
+use jiff::Zoned;
 use semver::{Prerelease, Version};
 
-use crate::{cli::VersionCommand, cmd::Command};
+use crate::{
+    cli::VersionCommand,
+    cmd::{forced_version, release_as, version_tags, Command},
+};
 
 enum Label {
     /// Bump major version (0.1.0 -> 1.0.0)
@@ -33,6 +39,41 @@ impl fmt::Display for Label {
     }
 }
 
+/// Increments `version`. A calendar version moves to the date of today instead.
+fn increment(version: &mut Version, increment: &Increment, versions: &VersionFormat) {
+    if versions.is_calendar() {
+        *version = versions.calendar_version(Some(version), increment, Zoned::now().date());
+        return;
+    }
+    match increment {
+        Increment::Major => version.increment_major(),
+        Increment::Minor => version.increment_minor(),
+        Increment::Patch => version.increment_patch(),
+        Increment::None => {}
+    }
+}
+
+/// The label of a version set by a `Release-As` footer.
+fn forced_label(last: &Version, next: &Version) -> Label {
+    if next.major != last.major {
+        Label::Major
+    } else if next.minor != last.minor {
+        Label::Minor
+    } else {
+        Label::Patch
+    }
+}
+
+/// The first line of the commit message.
+fn subject(commit: &impl CommitTrait) -> Result<String, ConvcoError> {
+    Ok(commit
+        .commit_message()?
+        .lines()
+        .next()
+        .unwrap_or_default()
+        .to_owned())
+}
+
 fn same_base_version(left: &Version, right: &Version) -> bool {
     left.major == right.major && left.minor == right.minor && left.patch == right.patch
 }
@@ -119,26 +160,62 @@ fn ensure_prerelease_base_is_unreleased<C: CommitTrait>(
 }
 
 impl VersionCommand {
-    fn get_version(
+    fn get_version(&self, config: &Config) -> Result<(Version, Label, String), ConvcoError> {
+        let parser = Self::parser(config);
+        let repo = open_repo()?;
+        self.get_version_in(&repo, &repo, &parser, config)
+    }
+
+    /// The commit parser of `config`.
+    fn parser(config: &Config) -> CommitParser {
+        CommitParser::builder()
+            .scope_regex(config.scope_regex.clone())
+            .strip_regex(config.strip_regex.clone())
+            .squash_handling(config.squash_handling)
+            .build()
+    }
+
+    /// The version of the commits of `commits`, the whole repository or one package of its history.
+    fn get_version_in<'a, R: Repo<'a>>(
         &self,
-        scope_regex: String,
-        strip_regex: String,
-        types: Vec<convco::Type>,
-        mut initial_bump_version: Version,
-        treat_major_zero_as_stable: bool,
+        repo: &'a R,
+        commits: &'a dyn Walk<'a, R::CommitTrait>,
+        parser: &'a CommitParser,
+        config: &Config,
     ) -> Result<(Version, Label, String), ConvcoError> {
-        let repo = open_repo()?;
-        let prefix = self.prefix.as_str();
+        let tags = TagFormat::new(&self.prefix, config)?;
+        let versions = tags.versions();
+        let mut initial_bump_version = match &self.initial_bump_version {
+            Some(version) => version.clone(),
+            None if versions.is_calendar() => {
+                versions.calendar_version(None, &Increment::None, Zoned::now().date())
+            }
+            None => config.initial_bump_version.clone(),
+        };
         let ignore_prereleases = self.bump || self.ignore_prereleases;
-        let semvers = repo.semver_tags(prefix)?;
-        let rev = Repo::revparse_single(&repo, &self.rev)?;
-        let last_version = repo.find_last_version(&rev, ignore_prereleases, &semvers)?;
+        let semvers = version_tags(repo, &tags, self.verbose)?.versions;
+        let rev = Repo::revparse_single(repo, &self.rev)?;
+        let last_version = commits.last_version(&rev, ignore_prereleases, &semvers)?;
         match last_version {
             None => {
-                let commit = Repo::revparse_single(&repo, &self.rev)?;
+                let commit = Repo::revparse_single(repo, &self.rev)?;
                 let commit_sha = CommitTrait::id(&commit);
                 let mut version = Version::new(0, 0, 0);
                 if self.bump {
+                    let options = RevWalkOptions {
+                        from_rev: vec![],
+                        to_rev: commit.clone(),
+                        first_parent: false,
+                        no_merge_commits: false,
+                        no_revert_commits: false,
+                        paths: self.paths.clone(),
+                        filters: self.filters.filters()?,
+                        parser,
+                    };
+                    if let Some((_, forced)) = release_as(commits, options, config, versions, None)?
+                    {
+                        initial_bump_version = forced;
+                    }
                     if self.prerelease.is_empty() {
                         let label = match (
                             initial_bump_version.major,
@@ -161,13 +238,13 @@ impl VersionCommand {
                         Ok((initial_bump_version, Label::Prerelease, commit_sha))
                     }
                 } else if self.patch {
-                    version.patch = 1;
+                    increment(&mut version, &Increment::Patch, versions);
                     Ok((version, Label::Patch, commit_sha))
                 } else if self.minor {
-                    version.minor = 1;
+                    increment(&mut version, &Increment::Minor, versions);
                     Ok((version, Label::Minor, commit_sha))
                 } else if self.major {
-                    version.major = 1;
+                    increment(&mut version, &Increment::Major, versions);
                     Ok((version, Label::Major, commit_sha))
                 } else {
                     Ok((version, Label::Patch, commit_sha))
@@ -175,13 +252,13 @@ impl VersionCommand {
             }
             Some((mut version, commit)) => {
                 let v = if self.major {
-                    version.increment_major();
+                    increment(&mut version, &Increment::Major, versions);
                     (version, Label::Major, CommitTrait::id(&commit))
                 } else if self.minor {
-                    version.increment_minor();
+                    increment(&mut version, &Increment::Minor, versions);
                     (version, Label::Minor, CommitTrait::id(&commit))
                 } else if self.patch {
-                    version.increment_patch();
+                    increment(&mut version, &Increment::Patch, versions);
                     (version, Label::Patch, CommitTrait::id(&commit))
                 } else if self.bump {
                     if version.is_prerelease() {
@@ -195,18 +272,8 @@ impl VersionCommand {
                             (version, Label::Prerelease, CommitTrait::id(&commit))
                         }
                     } else {
-                        let parser = CommitParser::builder()
-                            .scope_regex(scope_regex)
-                            .strip_regex(strip_regex)
-                            .build();
                         self.find_bump_version(
-                            &repo,
-                            commit,
-                            version,
-                            &parser,
-                            &types,
-                            &semvers,
-                            treat_major_zero_as_stable,
+                            repo, commits, commit, version, parser, config, &semvers,
                         )?
                     }
                 } else {
@@ -217,15 +284,16 @@ impl VersionCommand {
         }
     }
 
+    #[allow(clippy::too_many_arguments)]
     fn find_bump_version<'a, R, C>(
         &self,
         repo: &'a R,
+        commits: &'a dyn Walk<'a, C>,
         commit: C,
         last_version: semver::Version,
         parser: &'a CommitParser,
-        types: &[Type],
+        config: &Config,
         semvers: &[(Version, C)],
-        treat_major_zero_as_stable: bool,
     ) -> Result<(Version, Label, String), ConvcoError>
     where
         R: Repo<'a, CommitTrait = C>,
@@ -240,57 +308,147 @@ impl VersionCommand {
             no_merge_commits: false,
             no_revert_commits: false,
             paths: self.paths.clone(),
+            filters: self.filters.filters()?,
             parser,
         };
-        let revwalk = repo.revwalk(options)?;
-        let mut major = false;
-        let mut minor = false;
-        let mut patch = false;
+        let skipped = if self.explain && !self.paths.is_empty() {
+            Self::skipped_by_paths(commits, &options)?
+        } else {
+            Vec::new()
+        };
+        let revwalk = commits.walk(options)?;
+        // The first commit that bumps the major, minor or patch version.
+        let mut major = None;
+        let mut minor = None;
+        let mut patch = None;
 
+        let treat_major_zero_as_stable =
+            self.treat_major_zero_as_stable || config.treat_major_zero_as_stable;
         let major_version_zero = last_version.major == 0 && !treat_major_zero_as_stable;
         let mut commit_sha = None;
-        for commit in revwalk.flatten() {
+        let mut explained = Vec::new();
+        let mut breaking = false;
+        // The commit and version of the newest `Release-As` footer.
+        let mut release_as = None;
+        let versions = config.version_scheme.compile()?;
+        for commit in revwalk {
+            let commit = match commit {
+                Ok(commit) => commit,
+                Err((_, commit)) => {
+                    if self.explain {
+                        explained.push(format!(
+                            "{} {} [not conventional]",
+                            commit.short_id(),
+                            subject(&commit)?
+                        ));
+                    }
+                    continue;
+                }
+            };
             if commit_sha.is_none() {
                 commit_sha = Some(commit.commit.id());
             }
-            if commit.conventional_commit.is_breaking() {
-                if major_version_zero {
-                    minor = true;
-                } else {
-                    major = true;
+            let short_id = commit.commit.short_id();
+            // a squash commit can have one entry per squashed commit
+            for conventional_commit in &parser.expand(commit.conventional_commit) {
+                let option_commit_type = config
+                    .types
+                    .iter()
+                    .find(|x| commit_type_eq(&x.r#type, &conventional_commit.r#type));
+                let rule = config
+                    .bump_rules
+                    .iter()
+                    .enumerate()
+                    .find(|(_, rule)| rule.matches(conventional_commit));
+                let rule_increment =
+                    rule.and_then(|(_, rule)| rule.increment_of(conventional_commit));
+                if self.explain {
+                    let increment = match (rule_increment, option_commit_type) {
+                        (Some(increment), _) => increment.to_string(),
+                        (None, Some(ty)) => ty.increment.to_string(),
+                        (None, None) => "unknown type".to_owned(),
+                    };
+                    let breaking = match conventional_commit.is_breaking() {
+                        true => ", breaking",
+                        false => "",
+                    };
+                    let rule = match rule {
+                        Some((i, rule)) => format!(", bump rule {}: {rule}", i + 1),
+                        None => String::new(),
+                    };
+                    explained.push(format!(
+                        "{short_id} {} [{increment}{breaking}{rule}]",
+                        subject(&commit.commit)?
+                    ));
+                }
+                if release_as.is_none() {
+                    if let Some(footer) = config.release_as(conventional_commit) {
+                        let version = forced_version(footer, &versions, Some(&last_version))?;
+                        release_as = Some((short_id.clone(), version));
+                    }
+                }
+                // Nothing outweighs the first breaking change, the rest is only listed
+                // and searched for a `Release-As` footer.
+                if breaking {
+                    continue;
+                }
+                if conventional_commit.is_breaking() && rule_increment.is_none() {
+                    if major_version_zero {
+                        minor.get_or_insert(short_id.clone());
+                    } else {
+                        major.get_or_insert(short_id.clone());
+                    }
+                    breaking = true;
+                    continue;
                 }
-                break;
-            }
 
-            let option_commit_type = types
-                .iter()
-                .find(|x| commit_type_eq(&x.r#type, &commit.conventional_commit.r#type));
-
-            if let Some(some_commit_type) = option_commit_type {
-                match (&some_commit_type.increment, major_version_zero) {
-                    (Increment::Major, _) => major = true,
-                    (Increment::Minor, true) => patch = true,
-                    (Increment::Minor, false) => minor = true,
-                    (Increment::Patch, _) => patch = true,
-                    _ => {}
+                let commit_increment =
+                    rule_increment.or(option_commit_type.map(|ty| &ty.increment));
+                if let Some(commit_increment) = commit_increment {
+                    match (commit_increment, major_version_zero) {
+                        (Increment::Major, _) => major.get_or_insert(short_id.clone()),
+                        (Increment::Minor, true) => patch.get_or_insert(short_id.clone()),
+                        (Increment::Minor, false) => minor.get_or_insert(short_id.clone()),
+                        (Increment::Patch, _) => patch.get_or_insert(short_id.clone()),
+                        _ => continue,
+                    };
                 }
             }
         }
-        let label = match (major, minor, patch) {
-            (true, _, _) => {
-                last_version.increment_major();
-                Label::Major
+        let (label, bump, decided_by) = match (major, minor, patch) {
+            (Some(id), _, _) => (Label::Major, Increment::Major, Some(id)),
+            (None, Some(id), _) => (Label::Minor, Increment::Minor, Some(id)),
+            (None, None, Some(id)) => (Label::Patch, Increment::Patch, Some(id)),
+            // TODO what should be the behaviour? always increment patch? or stay on same version?
+            _ => (Label::Release, Increment::None, None),
+        };
+        if self.explain {
+            for line in &explained {
+                eprintln!("{line}");
             }
-            (false, true, _) => {
-                last_version.increment_minor();
-                Label::Minor
+            for line in &skipped {
+                eprintln!("{line} [skipped by --paths]");
+            }
+        }
+        let label = match release_as {
+            Some((id, version)) => {
+                let label = forced_label(&last_version, &version);
+                if self.explain {
+                    eprintln!("{label}: released as {} by {id}", versions.format(&version));
+                }
+                last_version = version;
+                label
             }
-            (false, false, true) => {
-                last_version.increment_patch();
-                Label::Patch
+            None => {
+                if self.explain {
+                    match decided_by {
+                        Some(id) => eprintln!("{label}: decided by {id}"),
+                        None => eprintln!("{label}: no commit bumps the version"),
+                    }
+                }
+                increment(&mut last_version, &bump, &versions);
+                label
             }
-            // TODO what should be the behaviour? always increment patch? or stay on same version?
-            _ => Label::Release,
         };
         let commit_sha = commit_sha.unwrap_or_default();
         if !self.prerelease.is_empty() {
@@ -299,31 +457,113 @@ impl VersionCommand {
         }
         Ok((last_version, label, commit_sha))
     }
-}
 
-impl Command for VersionCommand {
-    fn exec(&self, config: Config) -> anyhow::Result<()> {
-        let initial_bump_version = self
-            .initial_bump_version
-            .clone()
-            .unwrap_or(config.initial_bump_version);
-        let treat_major_zero_as_stable =
-            self.treat_major_zero_as_stable || config.treat_major_zero_as_stable;
-        let (version, label, commit_sha) = self.get_version(
-            config.scope_regex,
-            config.strip_regex,
-            config.types,
-            initial_bump_version,
-            treat_major_zero_as_stable,
-        )?;
-        if self.label {
-            println!("{label}");
+    /// The commits hidden by the `--paths` filter of `options`, as `<short id> <subject>`.
+    fn skipped_by_paths<'a, C: CommitTrait>(
+        commits: &'a dyn Walk<'a, C>,
+        options: &RevWalkOptions<'a, C>,
+    ) -> Result<Vec<String>, ConvcoError> {
+        let filtered = commits
+            .walk(options.clone())?
+            .map(|commit| match commit {
+                Ok(commit) => commit.commit.id(),
+                Err((_, commit)) => commit.id(),
+            })
+            .collect::<HashSet<_>>();
+        let all = RevWalkOptions {
+            paths: Vec::new(),
+            ..options.clone()
+        };
+        commits
+            .walk(all)?
+            .map(|commit| match commit {
+                Ok(commit) => commit.commit,
+                Err((_, commit)) => commit,
+            })
+            .filter(|commit| !filtered.contains(&commit.id()))
+            .map(|commit| Ok(format!("{} {}", commit.short_id(), subject(&commit)?)))
+            .collect()
+    }
+
+    /// The version that `convco version` would print.
+    pub(crate) fn version(&self, config: &Config) -> Result<Version, ConvcoError> {
+        self.get_version(config).map(|(version, _, _)| version)
+    }
+
+    /// Writes `version` to `files`, see [`bump_files`].
+    /// Returns the written paths, or the paths that would be written when `dry_run` is set.
+    pub(crate) fn write_bump_files(
+        files: &[BumpFile],
+        version: &str,
+        dry_run: bool,
+    ) -> Result<Vec<PathBuf>, ConvcoError> {
+        // the paths are relative to the root of the repository, not to the current directory
+        let root = Repo::workdir(&open_repo()?).unwrap_or_default();
+        let updates = bump_files::updates(files, &root, version)?;
+        if dry_run {
+            Ok(updates.into_iter().map(|update| update.path).collect())
+        } else {
+            bump_files::write(updates)
+        }
+    }
+
+    /// The version, label or commit sha to print, depending on the flags.
+    fn output(
+        &self,
+        config: &Config,
+        (version, label, commit_sha): (Version, Label, String),
+    ) -> Result<String, ConvcoError> {
+        let tags = TagFormat::new(&self.prefix, config)?;
+        Ok(if self.label {
+            label.to_string()
         } else if self.commit_sha {
-            println!("{commit_sha}");
+            commit_sha
         } else if self.print_prefix {
-            println!("{}{version}", self.prefix);
+            tags.tag(&version)
         } else {
-            println!("{version}");
+            tags.versions().format(&version)
+        })
+    }
+}
+
+impl Command for VersionCommand {
+    fn exec(&self, config: Config) -> anyhow::Result<()> {
+        let versions = config.version_scheme.compile()?;
+        if !self.all {
+            let computed = self.get_version(&config)?;
+            let version = versions.format(&computed.0);
+            println!("{}", self.output(&config, computed)?);
+            if self.write {
+                Self::write_bump_files(&config.bump_files, &version, false)?;
+            }
+            return Ok(());
+        }
+        if config.packages.is_empty() {
+            Err(ConvcoError::NoPackages)?;
+        }
+        // one walk of the history, shared by all packages
+        let parser = Self::parser(&config);
+        let repo = open_repo()?;
+        let rev = Repo::revparse_single(&repo, &self.rev)?;
+        let paths = config
+            .packages
+            .iter()
+            .map(|package| package.paths.clone())
+            .collect::<Vec<_>>();
+        let history = repo.history(&[rev], &paths)?;
+        for (i, package) in config.packages.iter().enumerate() {
+            let command = Self {
+                prefix: package.tag_prefix(),
+                paths: package.paths.clone(),
+                all: false,
+                ..self.clone()
+            };
+            let computed = command.get_version_in(&repo, &history.package(i), &parser, &config)?;
+            let version = versions.format(&computed.0);
+            println!("{} {}", package.name, command.output(&config, computed)?);
+            if self.write {
+                Self::write_bump_files(&package.bump_files, &version, false)?;
+            }
         }
         Ok(())
     }
@@ -0,0 +1,738 @@
+//! Writes a new version to the files listed in `bumpFiles`.
+//!
+//! The files are updated in place with line or span based replacements, so the formatting and
+//! comments of the files are kept.
+
+use std::{
+    collections::HashSet,
+    fs,
+    ops::Range,
+    path::{Path, PathBuf},
+};
+
+use convco::{BumpFile, BumpFileType, ConvcoError};
+use regex::Regex;
+
+/// The new content of a file.
+pub(crate) struct Update {
+    pub path: PathBuf,
+    pub content: String,
+}
+
+fn error(path: &Path, reason: impl Into<String>) -> ConvcoError {
+    ConvcoError::BumpFile {
+        path: path.display().to_string(),
+        reason: reason.into(),
+    }
+}
+
+fn read(path: &Path) -> Result<String, ConvcoError> {
+    fs::read_to_string(path).map_err(|e| error(path, e.to_string()))
+}
+
+/// Computes the updates of all `files`, relative to `root`, without writing them.
+pub(crate) fn updates(
+    files: &[BumpFile],
+    root: &Path,
+    version: &str,
+) -> Result<Vec<Update>, ConvcoError> {
+    let mut updates = Vec::new();
+    for file in files {
+        let path = root.join(&file.path);
+        let path = path.as_path();
+        match file.file_type() {
+            BumpFileType::Cargo => updates.extend(cargo(path, version)?),
+            BumpFileType::Npm => {
+                let content = read(path)?;
+                let content = json(&content, &[&["version"]], version)
+                    .map_err(|reason| error(path, reason))?;
+                updates.push(Update {
+                    path: path.to_owned(),
+                    content,
+                });
+            }
+            BumpFileType::NpmLock => {
+                let content = read(path)?;
+                let content = json(
+                    &content,
+                    &[&["version"], &["packages", "", "version"]],
+                    version,
+                )
+                .map_err(|reason| error(path, reason))?;
+                updates.push(Update {
+                    path: path.to_owned(),
+                    content,
+                });
+            }
+            BumpFileType::Python => {
+                let content = read(path)?;
+                let content = toml(&content, &["project", "tool.poetry"], version)
+                    .ok_or_else(|| error(path, "no version in [project] or [tool.poetry]"))?;
+                updates.push(Update {
+                    path: path.to_owned(),
+                    content,
+                });
+            }
+            BumpFileType::Plain => {
+                let content = read(path)?;
+                let trimmed = content.trim_end();
+                let content = format!("{version}{}", &content[trimmed.len()..]);
+                updates.push(Update {
+                    path: path.to_owned(),
+                    content,
+                });
+            }
+            BumpFileType::Regex => {
+                let pattern = file
+                    .pattern
+                    .as_deref()
+                    .ok_or_else(|| error(path, "the regex type requires a pattern"))?;
+                let regex = Regex::new(pattern).map_err(|e| error(path, e.to_string()))?;
+                let content = read(path)?;
+                let content = replace_regex(&content, &regex, version)
+                    .map_err(|reason| error(path, reason))?;
+                updates.push(Update {
+                    path: path.to_owned(),
+                    content,
+                });
+            }
+        }
+    }
+    Ok(updates)
+}
+
+/// Writes the updates and returns the paths of the written files.
+pub(crate) fn write(updates: Vec<Update>) -> Result<Vec<PathBuf>, ConvcoError> {
+    updates
+        .into_iter()
+        .map(|Update { path, content }| {
+            fs::write(&path, content).map_err(|e| error(&path, e.to_string()))?;
+            Ok(path)
+        })
+        .collect()
+}
+
+/// Range of the quoted value of `key = "value"`, without the quotes.
+fn string_value(line: &str, key: &str) -> Option<Range<usize>> {
+    let rest = line.trim_start().strip_prefix(key)?;
+    let value = rest.trim_start().strip_prefix('=')?.trim_start();
+    let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+    let start = line.len() - value.len() + 1;
+    let end = start + value[1..].find(quote)?;
+    Some(start..end)
+}
+
+/// Name of a `[table]` or `[[array]]` header.
+fn section(line: &str) -> Option<&str> {
+    let line = line.trim();
+    if let Some(line) = line.strip_prefix("[[") {
+        return line.split_once("]]").map(|(name, _)| name.trim());
+    }
+    line.strip_prefix('[')
+        .and_then(|l| l.split_once(']'))
+        .map(|(name, _)| name.trim())
+}
+
+/// Byte range of the value of `key` in the table `section_name`.
+fn toml_range(content: &str, section_name: &str, key: &str) -> Option<Range<usize>> {
+    let mut current = "";
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if let Some(name) = section(line) {
+            current = name;
+        } else if current == section_name {
+            if let Some(range) = string_value(line, key) {
+                return Some(offset + range.start..offset + range.end);
+            }
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Value of `key` in the table `section_name`, e.g. the `name` of `[package]`.
+fn toml_value<'a>(content: &'a str, section_name: &str, key: &str) -> Option<&'a str> {
+    toml_range(content, section_name, key).map(|range| &content[range])
+}
+
+/// Replaces the `version` of every one of `sections` that has one.
+fn toml_all(content: &str, sections: &[&str], version: &str) -> Option<String> {
+    sections.iter().fold(None, |updated, section| {
+        toml(updated.as_deref().unwrap_or(content), &[section], version).or(updated)
+    })
+}
+
+/// Replaces the `version` of the first of `sections` that has one.
+fn toml(content: &str, sections: &[&str], version: &str) -> Option<String> {
+    sections
+        .iter()
+        .find_map(|section| toml_range(content, section, "version"))
+        .map(|range| replace(content, range, version))
+}
+
+fn replace(content: &str, range: Range<usize>, value: &str) -> String {
+    format!(
+        "{}{value}{}",
+        &content[..range.start],
+        &content[range.end..]
+    )
+}
+
+/// Whether the package inherits its version with `version.workspace = true`.
+fn inherits_version(content: &str) -> bool {
+    let mut current = "";
+    for line in content.lines() {
+        if let Some(name) = section(line) {
+            current = name;
+        } else if current == "package" {
+            let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if line.starts_with("version.workspace=true") {
+                return true;
+            }
+        } else if current == "package.version" {
+            let line: String = line.chars().filter(|c| !c.is_whitespace()).collect();
+            if line.starts_with("workspace=true") {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// The quoted strings of the `members` array of `[workspace]`.
+fn workspace_members(content: &str) -> Vec<String> {
+    let mut current = "";
+    let mut members = None::<String>;
+    for line in content.lines() {
+        if let Some(members) = members.as_mut() {
+            members.push_str(line);
+            if line.contains(']') {
+                break;
+            }
+            continue;
+        }
+        if let Some(name) = section(line) {
+            current = name;
+        } else if current == "workspace" {
+            let trimmed = line.trim_start();
+            if let Some(rest) = trimmed.strip_prefix("members") {
+                if let Some(rest) = rest.trim_start().strip_prefix('=') {
+                    members = Some(rest.to_owned());
+                    if rest.contains(']') {
+                        break;
+                    }
+                }
+            }
+        }
+    }
+    let Some(members) = members else {
+        return Vec::new();
+    };
+    let members = members
+        .split_once('[')
+        .map(|(_, m)| m)
+        .unwrap_or_default()
+        .split(']')
+        .next()
+        .unwrap_or_default();
+    members
+        .split(',')
+        .map(|m| m.trim().trim_matches(|c| c == '"' || c == '\''))
+        .filter(|m| !m.is_empty())
+        .map(String::from)
+        .collect()
+}
+
+/// Directories of the workspace members, expanding a trailing `/*`.
+fn member_dirs(root: &Path, members: &[String]) -> Vec<PathBuf> {
+    let mut dirs = Vec::new();
+    for member in members {
+        if let Some(parent) = member.strip_suffix("/*") {
+            let Ok(entries) = fs::read_dir(root.join(parent)) else {
+                continue;
+            };
+            let mut found: Vec<_> = entries
+                .flatten()
+                .map(|entry| entry.path())
+                .filter(|path| path.join("Cargo.toml").is_file())
+                .collect();
+            found.sort();
+            dirs.extend(found);
+        } else {
+            dirs.push(root.join(member));
+        }
+    }
+    dirs
+}
+
+/// Updates a `Cargo.toml`, the manifests of its workspace members, the requirements between them
+/// and the `Cargo.lock`.
+fn cargo(path: &Path, version: &str) -> Result<Vec<Update>, ConvcoError> {
+    let content = read(path)?;
+    let root = path.parent().unwrap_or(Path::new(""));
+    let mut updates = Vec::new();
+    let mut names = HashSet::new();
+
+    let workspace_version = toml_value(&content, "workspace.package", "version").is_some();
+    // a workspace root can be a package as well, both versions are updated
+    let updated = toml_all(&content, &["package", "workspace.package"], version);
+    if let Some(name) = toml_value(&content, "package", "name") {
+        if toml_value(&content, "package", "version").is_some()
+            || workspace_version && inherits_version(&content)
+        {
+            names.insert(name.to_owned());
+        }
+    }
+    let members = workspace_members(&content);
+    if updated.is_none() && members.is_empty() {
+        return Err(error(
+            path,
+            "no version in [package] or [workspace.package]",
+        ));
+    }
+    // the manifests with their content before and after the update
+    let mut manifests = vec![(path.to_owned(), content.clone(), updated)];
+
+    for dir in member_dirs(root, &members) {
+        let manifest = dir.join("Cargo.toml");
+        if manifest == path {
+            continue;
+        }
+        let member = read(&manifest)?;
+        let Some(name) = toml_value(&member, "package", "name") else {
+            continue;
+        };
+        let mut updated = None;
+        if inherits_version(&member) {
+            if workspace_version {
+                names.insert(name.to_owned());
+            }
+        } else {
+            updated = toml(&member, &["package"], version);
+            if updated.is_some() {
+                names.insert(name.to_owned());
+            }
+        }
+        manifests.push((manifest, member, updated));
+    }
+
+    for (path, content, updated) in manifests {
+        let updated = dependency_versions(updated.as_deref().unwrap_or(&content), &names, version);
+        if updated != content {
+            updates.push(Update {
+                path,
+                content: updated,
+            });
+        }
+    }
+
+    let lock = root.join("Cargo.lock");
+    if lock.is_file() && !names.is_empty() {
+        let content = read(&lock)?;
+        updates.push(Update {
+            content: cargo_lock(&content, &names, version),
+            path: lock,
+        });
+    }
+    Ok(updates)
+}
+
+/// Whether `section` is a table of dependencies, e.g. `[dev-dependencies]`,
+/// `[workspace.dependencies]` or `[target.'cfg(unix)'.dependencies]`.
+fn is_dependencies(section: &str) -> bool {
+    matches!(
+        section.rsplit('.').next(),
+        Some("dependencies" | "dev-dependencies" | "build-dependencies")
+    )
+}
+
+/// Range of the `version` of an inline dependency on one of `names`,
+/// e.g. `core = { path = "../core", version = "1.0.0" }`.
+fn inline_dependency_version(line: &str, names: &HashSet<String>) -> Option<Range<usize>> {
+    let (key, table) = line.split_once('=')?;
+    let table = table.trim_start().strip_prefix('{')?;
+    let mut offset = line.len() - table.len();
+    let mut package = None;
+    let mut version = None;
+    for entry in table.split(',') {
+        if let Some(range) = string_value(entry, "package") {
+            package = Some(&entry[range]);
+        } else if let Some(range) = string_value(entry, "version") {
+            version = Some(offset + range.start..offset + range.end);
+        }
+        offset += entry.len() + 1;
+    }
+    let name = package.unwrap_or_else(|| key.trim().trim_matches(|c| c == '"' || c == '\''));
+    version.filter(|_| names.contains(name))
+}
+
+/// Replaces the version requirements of the dependencies on the packages in `names`,
+/// in inline tables and in `[dependencies.<name>]` tables. An operator such as `=` is kept.
+fn dependency_versions(content: &str, names: &HashSet<String>, version: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut current = "";
+    for line in content.split_inclusive('\n') {
+        if let Some(name) = section(line) {
+            current = name;
+            output.push_str(line);
+            continue;
+        }
+        let range = if is_dependencies(current) {
+            inline_dependency_version(line, names)
+        } else if let Some((_, key)) = current
+            .rsplit_once('.')
+            .filter(|(table, _)| is_dependencies(table))
+        {
+            let key = key.trim_matches(|c| c == '"' || c == '\'');
+            let name = toml_value(content, current, "package").unwrap_or(key);
+            string_value(line, "version").filter(|_| names.contains(name))
+        } else {
+            None
+        };
+        match range {
+            Some(range) => {
+                let requirement = &line[range.clone()];
+                let start =
+                    range.start + requirement.find(|c: char| c.is_ascii_digit()).unwrap_or(0);
+                output.push_str(&replace(line, start..range.end, version));
+            }
+            None => output.push_str(line),
+        }
+    }
+    output
+}
+
+/// Replaces the version of the `[[package]]` entries named in `names`.
+fn cargo_lock(content: &str, names: &HashSet<String>, version: &str) -> String {
+    let mut output = String::with_capacity(content.len());
+    let mut current = None;
+    for line in content.split_inclusive('\n') {
+        if section(line).is_some() {
+            current = None;
+        } else if let Some(range) = string_value(line, "name") {
+            current = Some(line[range].to_owned());
+        } else if current.as_ref().is_some_and(|name| names.contains(name)) {
+            if let Some(range) = string_value(line, "version") {
+                output.push_str(&replace(line, range, version));
+                continue;
+            }
+        }
+        output.push_str(line);
+    }
+    output
+}
+
+/// Replaces the string values at `paths`. The first path must exist, the others are optional.
+fn json(content: &str, paths: &[&[&str]], version: &str) -> Result<String, String> {
+    serde_json::from_str::<serde_json::Value>(content).map_err(|e| e.to_string())?;
+    let mut ranges = Vec::new();
+    for (i, path) in paths.iter().enumerate() {
+        let mut scanner = JsonScanner {
+            json: content.as_bytes(),
+            pos: 0,
+        };
+        match scanner.find(path) {
+            Some(range) => ranges.push(range),
+            None if i == 0 => return Err(format!("no string at `{}`", path.join("."))),
+            None => {}
+        }
+    }
+    ranges.sort_by_key(|range| range.start);
+    let mut output = content.to_owned();
+    for range in ranges.into_iter().rev() {
+        output.replace_range(range, version);
+    }
+    Ok(output)
+}
+
+fn replace_regex(content: &str, regex: &Regex, version: &str) -> Result<String, String> {
+    let mut ranges: Vec<_> = regex
+        .captures_iter(content)
+        .filter_map(|captures| captures.name("version"))
+        .map(|m| m.range())
+        .collect();
+    if ranges.is_empty() {
+        return Err(format!("`{regex}` has no match with a `version` group"));
+    }
+    let mut output = content.to_owned();
+    ranges.reverse();
+    for range in ranges {
+        output.replace_range(range, version);
+    }
+    Ok(output)
+}
+
+/// Finds the byte range of a string value in a valid JSON document.
+struct JsonScanner<'a> {
+    json: &'a [u8],
+    pos: usize,
+}
+
+impl JsonScanner<'_> {
+    fn whitespace(&mut self) {
+        while self.json.get(self.pos).is_some_and(u8::is_ascii_whitespace) {
+            self.pos += 1;
+        }
+    }
+
+    /// Skips a string and returns the range of its content, without the quotes.
+    fn string(&mut self) -> Range<usize> {
+        self.pos += 1;
+        let start = self.pos;
+        while let Some(&c) = self.json.get(self.pos) {
+            match c {
+                b'\\' => self.pos += 2,
+                b'"' => break,
+                _ => self.pos += 1,
+            }
+        }
+        let end = self.pos;
+        self.pos += 1;
+        start..end
+    }
+
+    fn skip_value(&mut self) {
+        self.whitespace();
+        match self.json.get(self.pos) {
+            Some(b'"') => {
+                self.string();
+            }
+            Some(b'{' | b'[') => {
+                let mut depth = 0;
+                while let Some(&c) = self.json.get(self.pos) {
+                    match c {
+                        b'"' => {
+                            self.string();
+                            continue;
+                        }
+                        b'{' | b'[' => depth += 1,
+                        b'}' | b']' => {
+                            depth -= 1;
+                            if depth == 0 {
+                                self.pos += 1;
+                                return;
+                            }
+                        }
+                        _ => {}
+                    }
+                    self.pos += 1;
+                }
+            }
+            _ => {
+                while self
+                    .json
+                    .get(self.pos)
+                    .is_some_and(|c| !matches!(c, b',' | b'}' | b']') && !c.is_ascii_whitespace())
+                {
+                    self.pos += 1;
+                }
+            }
+        }
+    }
+
+    fn find(&mut self, path: &[&str]) -> Option<Range<usize>> {
+        self.whitespace();
+        let Some((key, rest)) = path.split_first() else {
+            return (self.json.get(self.pos) == Some(&b'"')).then(|| self.string());
+        };
+        if self.json.get(self.pos) != Some(&b'{') {
+            return None;
+        }
+        self.pos += 1;
+        loop {
+            self.whitespace();
+            match self.json.get(self.pos)? {
+                b'}' => return None,
+                b',' => {
+                    self.pos += 1;
+                    continue;
+                }
+                b'"' => {}
+                _ => return None,
+            }
+            let name = self.string();
+            self.whitespace();
+            self.pos += 1; // `:`
+            if &self.json[name] == key.as_bytes() {
+                return self.find(rest);
+            }
+            self.skip_value();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn toml_replaces_the_version_of_the_section() {
+        let content = r#"[dependencies]
+version = "0.1.0"
+
+[package]
+name = "app"
+version = "1.2.3" # keep
+"#;
+        assert_eq!(
+            toml(content, &["package"], "1.3.0").unwrap(),
+            content.replace("1.2.3", "1.3.0")
+        );
+        assert!(toml(content, &["project"], "1.3.0").is_none());
+    }
+
+    #[test]
+    fn toml_all_replaces_the_version_of_every_section() {
+        let content =
+            "[package]\nversion = \"1.0.0\"\n\n[workspace.package]\nversion = \"1.0.0\"\n";
+        assert_eq!(
+            toml_all(content, &["package", "workspace.package"], "1.1.0").unwrap(),
+            content.replace("1.0.0", "1.1.0")
+        );
+        assert!(toml_all(content, &["project"], "1.1.0").is_none());
+    }
+
+    #[test]
+    fn pyproject_prefers_project() {
+        let content = "[tool.poetry]\nversion = '0.1.0'\n\n[project]\nversion = \"0.2.0\"\n";
+        assert_eq!(
+            toml(content, &["project", "tool.poetry"], "1.0.0").unwrap(),
+            "[tool.poetry]\nversion = '0.1.0'\n\n[project]\nversion = \"1.0.0\"\n"
+        );
+    }
+
+    #[test]
+    fn workspace_members_are_parsed() {
+        let content = "[workspace]\nmembers = [\n  \"crates/*\",\n  'tool',\n]\n\n[package]\n";
+        assert_eq!(workspace_members(content), ["crates/*", "tool"]);
+        assert_eq!(
+            workspace_members("[workspace]\nmembers = [\"a\", \"b\"]\n"),
+            ["a", "b"]
+        );
+    }
+
+    #[test]
+    fn inherited_version() {
+        assert!(inherits_version("[package]\nversion.workspace = true\n"));
+        assert!(inherits_version(
+            "[package.version]\nworkspace = true\n[dependencies]\n"
+        ));
+        assert!(!inherits_version("[package]\nversion = \"1.0.0\"\n"));
+    }
+
+    #[test]
+    fn dependency_versions_follow_the_members() {
+        let content = r#"[package]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+core = { path = "../core", version = "1.0.0" }
+cli = { package = "core-cli", path = "../cli", version = "=1.0.0", features = ["a", "b"] }
+serde = { version = "1.0.0" }
+version = "1.0.0"
+
+[dev-dependencies.macros]
+path = "../macros"
+version = "^1.0.0"
+
+[target.'cfg(unix)'.build-dependencies]
+"core" = { version = "1.0.0", path = "../core" }
+"#;
+        let names = HashSet::from([
+            "core".to_owned(),
+            "core-cli".to_owned(),
+            "macros".to_owned(),
+        ]);
+        assert_eq!(
+            dependency_versions(content, &names, "1.1.0"),
+            r#"[package]
+name = "app"
+version = "1.0.0"
+
+[dependencies]
+core = { path = "../core", version = "1.1.0" }
+cli = { package = "core-cli", path = "../cli", version = "=1.1.0", features = ["a", "b"] }
+serde = { version = "1.0.0" }
+version = "1.0.0"
+
+[dev-dependencies.macros]
+path = "../macros"
+version = "^1.1.0"
+
+[target.'cfg(unix)'.build-dependencies]
+"core" = { version = "1.1.0", path = "../core" }
+"#
+        );
+    }
+
+    #[test]
+    fn cargo_lock_updates_only_named_packages() {
+        let content = r#"[[package]]
+name = "app"
+version = "1.0.0"
+dependencies = [
+ "serde",
+]
+
+[[package]]
+name = "serde"
+version = "1.0.0"
+"#;
+        let names = HashSet::from(["app".to_owned()]);
+        assert_eq!(
+            cargo_lock(content, &names, "1.1.0"),
+            content.replacen("1.0.0", "1.1.0", 1)
+        );
+    }
+
+    #[test]
+    fn json_replaces_the_top_level_version() {
+        let content = r#"{
+  "name": "app",
+  "dependencies": { "version": "1.0.0", "x": [1, {"version": "2"}] },
+  "escaped": "\"version\": \"0.0.0\"",
+  "version": "1.0.0"
+}
+"#;
+        assert_eq!(
+            json(content, &[&["version"]], "2.0.0").unwrap(),
+            content.replace(
+                r#""version": "1.0.0"
+"#,
+                r#""version": "2.0.0"
+"#
+            )
+        );
+    }
+
+    #[test]
+    fn json_package_lock() {
+        let content = r#"{"name":"app","version":"1.0.0","lockfileVersion":3,"packages":{"":{"name":"app","version":"1.0.0"},"node_modules/x":{"version":"1.0.0"}}}"#;
+        assert_eq!(
+            json(
+                content,
+                &[&["version"], &["packages", "", "version"]],
+                "1.1.0"
+            )
+            .unwrap(),
+            content.replacen("1.0.0", "1.1.0", 2)
+        );
+    }
+
+    #[test]
+    fn json_without_version() {
+        assert!(json(r#"{"name": "app"}"#, &[&["version"]], "1.0.0").is_err());
+        assert!(json("{", &[&["version"]], "1.0.0").is_err());
+    }
+
+    #[test]
+    fn regex_replaces_the_version_group() {
+        let regex = Regex::new(r"appVersion: (?P<version>\S+)").unwrap();
+        assert_eq!(
+            replace_regex("version: 1\nappVersion: 0.1.0\n", &regex, "0.2.0").unwrap(),
+            "version: 1\nappVersion: 0.2.0\n"
+        );
+        assert!(replace_regex("version: 1\n", &regex, "0.2.0").is_err());
+    }
+}
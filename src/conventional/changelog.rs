@@ -12,15 +12,16 @@ use jiff::civil::Date;
 use serde::Serialize;
 use walkdir::WalkDir;
 
-use super::config::Config;
-use crate::ConvcoError;
+use super::config::{Config, Platform};
+use crate::{ConvcoError, Signature};
 
 const TEMPLATE: &str = include_str!("changelog/template.hbs");
 const HEADER: &str = include_str!("changelog/header.hbs");
 const FOOTER: &str = include_str!("changelog/footer.hbs");
 const COMMIT: &str = include_str!("changelog/commit.hbs");
+const RELEASE_NOTES: &str = include_str!("changelog/release-notes.hbs");
 
-#[derive(Debug, Serialize)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Reference<'a> {
     pub action: Option<String>,
     pub owner: &'a str,
@@ -41,7 +42,19 @@ pub struct NoteGroup {
     pub notes: Vec<Note>,
 }
 
-#[derive(Debug, Serialize)]
+/// The author, committer or a co-author of a commit.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Person {
+    pub name: String,
+    pub email: String,
+    /// The user name on the hosting platform, known from a no-reply email address.
+    pub user: Option<String>,
+    /// The profile link, rendered with `userUrlFormat` if the user name and the `host` are known.
+    pub url: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct CommitContext<'a> {
     pub hash: String,
@@ -51,12 +64,31 @@ pub struct CommitContext<'a> {
     pub scope: Option<String>,
     pub short_hash: String,
     pub references: Vec<Reference<'a>>,
+    pub author: Person,
+    pub committer: Person,
+    /// The people of the `Co-authored-by` footers.
+    pub co_authors: Vec<Person>,
 }
 
 #[derive(Debug, Serialize)]
 pub struct CommitGroup<'a> {
-    pub title: &'a str,
+    pub title: Cow<'a, str>,
+    pub commits: Vec<CommitContext<'a>>,
+    /// The commits of this group by scope, with `groupBy: typeScope`.
+    pub scopes: Vec<ScopeGroup<'a>>,
+}
+
+/// The commits of a scope.
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ScopeGroup<'a> {
+    /// The scope, or `unscopedSection` for commits without a scope.
+    pub title: String,
+    pub scope: Option<String>,
+    /// The commits of this scope, with `groupBy: typeScope`.
     pub commits: Vec<CommitContext<'a>>,
+    /// The commits of this scope by type, with `groupBy: scopeType`.
+    pub commit_groups: Vec<CommitGroup<'a>>,
 }
 
 #[derive(Debug, Serialize)]
@@ -76,19 +108,29 @@ pub struct ContextBase<'a> {
     pub version: Cow<'a, str>,
     pub date: Option<Date>,
     pub is_patch: bool,
+    /// The commits by type. Always set, also when grouped by scope.
     pub commit_groups: Vec<CommitGroup<'a>>,
+    /// The commits by scope and then type, with `groupBy: scopeType`.
+    pub scope_groups: Vec<ScopeGroup<'a>>,
     pub note_groups: Vec<NoteGroup>,
+    /// The authors and co-authors of the commits, once per email address, ordered by name.
+    pub contributors: Vec<Person>,
     pub previous_tag: String,
     pub current_tag: Cow<'a, str>,
     pub host: Option<String>,
     pub owner: Option<String>,
     pub repository: Option<String>,
+    /// The platform that links issues, commits and mentions of the release notes.
+    pub platform: Option<Platform>,
+    /// Serialized by `Context`, which only links when both tags are set.
+    #[serde(skip_serializing)]
     pub link_compare: bool,
     pub link_references: bool,
 }
 
 pub struct ContextBuilder<'a> {
     handlebars: Handlebars<'a>,
+    config: &'a Config,
 }
 
 impl<'a> ContextBuilder<'a> {
@@ -106,7 +148,34 @@ impl<'a> ContextBuilder<'a> {
         handlebars
             .register_template_string("user_url_format", config.user_url_format.as_str())
             .map_err(Box::new)?;
-        Ok(Self { handlebars })
+        Ok(Self { handlebars, config })
+    }
+
+    /// The person of a commit, with the profile link if the user name is known.
+    pub fn person(&self, signature: Signature) -> Result<Person, ConvcoError> {
+        let user = platform_user(&signature.email);
+        let url = match (&user, &self.config.host) {
+            (Some(user), Some(_)) => Some(
+                self.handlebars
+                    .render(
+                        "user_url_format",
+                        &serde_json::json!({
+                            "host": self.config.host,
+                            "owner": self.config.owner,
+                            "repository": self.config.repository,
+                            "user": user,
+                        }),
+                    )
+                    .map_err(Box::new)?,
+            ),
+            _ => None,
+        };
+        Ok(Person {
+            name: signature.name,
+            email: signature.email,
+            user,
+            url,
+        })
     }
 
     pub fn build(&self, context_base: ContextBase<'a>) -> Result<Context<'a>, ConvcoError> {
@@ -135,6 +204,28 @@ impl<'a> ContextBuilder<'a> {
     }
 }
 
+/// The user name of a no-reply email address of GitHub or GitLab.
+fn platform_user(email: &str) -> Option<String> {
+    let (local, domain) = email.rsplit_once('@')?;
+    let user = match domain.to_ascii_lowercase().as_str() {
+        // `<id>+<user>` or `<user>`
+        "users.noreply.github.com" => local.rsplit_once('+').map_or(local, |(_, user)| user),
+        // `<id>-<user>`
+        "users.noreply.gitlab.com" => local.split_once('-')?.1,
+        _ => return None,
+    };
+    (!user.is_empty()).then(|| user.to_owned())
+}
+
+/// Parses the value of a `Co-authored-by` footer: `Name <email>`.
+pub fn parse_co_author(value: &str) -> Option<Signature> {
+    let (name, email) = value.trim().strip_suffix('>')?.rsplit_once('<')?;
+    Some(Signature {
+        name: name.trim().to_owned(),
+        email: email.trim().to_owned(),
+    })
+}
+
 pub struct ChangelogWriter<W: io::Write> {
     writer: W,
     handlebars: Handlebars<'static>,
@@ -186,6 +277,14 @@ impl<W: io::Write> ChangelogWriter<W> {
                 .register_partial("footer", replace_url_formats(FOOTER, config))
                 .map_err(Box::new)?;
         }
+        if !handlebars.has_template("release-notes") {
+            handlebars
+                .register_template_string(
+                    "release-notes",
+                    replace_url_formats(RELEASE_NOTES, config),
+                )
+                .map_err(Box::new)?;
+        }
 
         Ok(Self { writer, handlebars })
     }
@@ -202,4 +301,47 @@ impl<W: io::Write> ChangelogWriter<W> {
             .map_err(Box::new)?;
         Ok(())
     }
+
+    /// Writes the notes of a single version with the `release-notes` template, without heading.
+    pub fn write_release_notes(&mut self, context: &Context<'_>) -> Result<(), ConvcoError> {
+        let writer = &mut self.writer;
+        self.handlebars
+            .render_to_write("release-notes", context, writer)
+            .map_err(Box::new)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn platform_users_from_no_reply_emails() {
+        assert_eq!(
+            platform_user("12345+octocat@users.noreply.github.com").as_deref(),
+            Some("octocat")
+        );
+        assert_eq!(
+            platform_user("octocat@users.noreply.github.com").as_deref(),
+            Some("octocat")
+        );
+        assert_eq!(
+            platform_user("123-jane.doe@users.noreply.gitlab.com").as_deref(),
+            Some("jane.doe")
+        );
+        assert_eq!(platform_user("octocat@example.com"), None);
+    }
+
+    #[test]
+    fn co_author_footer_values() {
+        assert_eq!(
+            parse_co_author("Jane Doe <jane@example.com>"),
+            Some(Signature {
+                name: "Jane Doe".into(),
+                email: "jane@example.com".into()
+            })
+        );
+        assert_eq!(parse_co_author("Jane Doe"), None);
+    }
 }
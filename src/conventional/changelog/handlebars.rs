@@ -1,9 +1,10 @@
-use std::borrow::Cow;
+use std::{borrow::Cow, sync::OnceLock};
 
 use handlebars::{
     no_escape, Context, Handlebars, Helper, HelperDef, HelperResult, Output, RenderContext,
     Renderable, StringOutput,
 };
+use regex::{Captures, Regex};
 
 fn word_wrap_acc<'a>(
     mut acc: Vec<Cow<'a, str>>,
@@ -71,11 +72,58 @@ impl HelperDef for WordWrapBlock {
     }
 }
 
+/// Helper for handlebars, links the `@user` mentions to the user url given as parameter
+///
+/// ```hbs
+/// {{#link-mentions "https://github.com/"}}
+/// Thanks @octocat
+/// {{/link-mentions}}
+/// ```
+struct LinkMentionsBlock;
+
+impl HelperDef for LinkMentionsBlock {
+    fn call<'reg: 'rc, 'rc>(
+        &self,
+        h: &Helper<'rc>,
+        r: &'reg Handlebars<'reg>,
+        ctx: &'rc Context,
+        rc: &mut RenderContext<'reg, 'rc>,
+        out: &mut dyn Output,
+    ) -> HelperResult {
+        let user_url = h
+            .param(0)
+            .and_then(|param| param.value().as_str())
+            .unwrap_or_default();
+        let mut text = StringOutput::new();
+        h.template()
+            .map(|t| t.render(r, ctx, rc, &mut text))
+            .unwrap_or(Ok(()))?;
+        let text = text.into_string()?;
+        out.write(&link_mentions(&text, user_url))?;
+        Ok(())
+    }
+}
+
+fn link_mentions<'a>(text: &'a str, user_url: &str) -> Cow<'a, str> {
+    static MENTION: OnceLock<Regex> = OnceLock::new();
+    // a mention is not part of a word or an email address
+    let mention = MENTION.get_or_init(|| {
+        Regex::new(r"(^|[^\w.@/])@([[:alnum:]](?:[[:alnum:]-]*[[:alnum:]])?)").unwrap()
+    });
+    mention.replace_all(text, |captures: &Captures| {
+        format!(
+            "{}[@{}]({user_url}{})",
+            &captures[1], &captures[2], &captures[2]
+        )
+    })
+}
+
 pub fn new(max: usize, disabled: bool) -> Handlebars<'static> {
     let mut handlebars = Handlebars::new();
     handlebars.set_strict_mode(true);
     handlebars.register_escape_fn(no_escape);
     handlebars.register_helper("word-wrap", Box::new(WordWrapBlock { max, disabled }));
+    handlebars.register_helper("link-mentions", Box::new(LinkMentionsBlock));
     handlebars
 }
 
@@ -102,6 +150,15 @@ mod tests {
         )
     }
 
+    #[test]
+    fn test_link_mentions() {
+        assert_eq!(
+            link_mentions("thanks @octo-cat and @me, not me@example.com", "https://host/"),
+            "thanks [@octo-cat](https://host/octo-cat) and [@me](https://host/me), not me@example.com"
+        );
+        assert_eq!(link_mentions("@start", "/"), "[@start](/start)");
+    }
+
     #[test]
     fn test_word_wrap() {
         let s = "The quick brown fox jumps over the lazy dog";
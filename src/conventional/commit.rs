@@ -1,7 +1,10 @@
-use std::fmt::{self, Display};
+use std::{
+    fmt::{self, Display},
+    sync::OnceLock,
+};
 
 use regex::Regex;
-use serde::Serialize;
+use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, PartialEq)]
@@ -62,6 +65,44 @@ impl ConventionalCommit {
     }
 }
 
+/// A commit that reverts another commit.
+#[derive(Debug, PartialEq)]
+pub struct Revert {
+    /// The subject of the reverted commit.
+    pub subject: String,
+    /// The id of the reverted commit, from the `This reverts commit <id>.` line of `git revert`.
+    pub id: Option<String>,
+}
+
+impl Revert {
+    /// Parses a `Revert "<subject>"` message of `git revert` or a `revert: <subject>` commit.
+    pub fn parse(msg: &str) -> Option<Self> {
+        static REVERT: OnceLock<Regex> = OnceLock::new();
+        static REVERTS_COMMIT: OnceLock<Regex> = OnceLock::new();
+        let revert = REVERT.get_or_init(|| {
+            Regex::new(r#"^(?:Revert "(?<git>.*)"|(?i:revert)(?:\([^)]*\))?!?: (?<desc>.*))$"#)
+                .unwrap()
+        });
+        let reverts_commit = REVERTS_COMMIT
+            .get_or_init(|| Regex::new(r"(?m)^This reverts commit ([[:xdigit:]]{7,64})").unwrap());
+        let captures = revert.captures(msg.lines().next()?)?;
+        let subject = captures.name("git").or(captures.name("desc"))?.as_str();
+        Some(Self {
+            subject: subject.to_owned(),
+            id: reverts_commit
+                .captures(msg)
+                .map(|captures| captures[1].to_owned()),
+        })
+    }
+
+    /// `true` if `id` is the reverted commit, `id` or the reverted id can be abbreviated.
+    pub fn reverts(&self, id: &str) -> bool {
+        self.id
+            .as_ref()
+            .is_some_and(|reverted| id.starts_with(reverted.as_str()) || reverted.starts_with(id))
+    }
+}
+
 impl fmt::Display for ConventionalCommit {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.r#type)
@@ -82,6 +123,19 @@ pub enum ParseError {
     InvalidScope(String),
 }
 
+/// How the commits of a pull request are written in the changelog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum SquashHandling {
+    /// One entry per commit.
+    #[default]
+    Off,
+    /// One entry per conventional bullet of the body of a squash commit, e.g. `* feat: x`.
+    Expand,
+    /// The entry of a `Merge pull request #123 from ...` commit is the pull request title in its body.
+    PrTitle,
+}
+
 #[derive(Debug)]
 pub struct CommitParser {
     regex_first_line: Regex,
@@ -89,6 +143,7 @@ pub struct CommitParser {
     regex_footer: Regex,
     regex_references: Regex,
     regex_strip: Regex,
+    squash_handling: SquashHandling,
 }
 
 impl CommitParser {
@@ -96,7 +151,77 @@ impl CommitParser {
         CommitParserBuilder::new()
     }
 
+    /// Whether `line` is a footer, e.g. `Reviewed-by: Z` or `Refs #1`.
+    pub fn is_footer(&self, line: &str) -> bool {
+        !line.contains('\n') && self.regex_footer.is_match(line)
+    }
+
+    /// The entries of a squash commit with [`SquashHandling::Expand`]: the conventional bullets of the body
+    /// with the references of the description, such as the `(#123)` of the pull request.
+    /// The footers of the squash commit are kept on the first entry.
+    /// A breaking squash commit is kept as the first entry, so its breaking change is not lost.
+    /// Without conventional bullets the commit is the only entry.
+    pub fn expand(&self, commit: ConventionalCommit) -> Vec<ConventionalCommit> {
+        if self.squash_handling != SquashHandling::Expand {
+            return vec![commit];
+        }
+        let mut entries: Vec<_> = commit
+            .body
+            .iter()
+            .flat_map(|body| body.lines())
+            .filter_map(|line| line.strip_prefix("* ").or_else(|| line.strip_prefix("- ")))
+            .filter_map(|line| self.parse(line.trim()).ok())
+            .collect();
+        let Some(first) = entries.first_mut() else {
+            return vec![commit];
+        };
+        let description = commit.description.clone();
+        if commit.is_breaking() {
+            entries.insert(0, commit);
+        } else {
+            first.footers = commit.footers;
+        }
+        for entry in &mut entries {
+            for captures in self.regex_references.captures_iter(&description) {
+                let reference = Reference {
+                    action: None,
+                    prefix: captures[1].into(),
+                    issue: captures[2].into(),
+                };
+                if !entry.references.contains(&reference) {
+                    entry.references.push(reference);
+                }
+            }
+        }
+        entries
+    }
+
+    /// The number and the message of the pull request of a GitHub merge commit,
+    /// with [`SquashHandling::PrTitle`].
+    fn pull_request_title<'a>(&self, msg: &'a str) -> Option<(&'a str, &'a str)> {
+        if self.squash_handling != SquashHandling::PrTitle {
+            return None;
+        }
+        let (first, title) = msg.split_once('\n')?;
+        let (number, _) = first
+            .strip_prefix("Merge pull request #")?
+            .split_once(" from ")?;
+        Some((number, title.trim_start()))
+    }
+
     pub fn parse(&self, msg: &str) -> Result<ConventionalCommit, ParseError> {
+        if let Some((number, title)) = self.pull_request_title(msg) {
+            let mut commit = self.parse(title)?;
+            let reference = Reference {
+                action: None,
+                prefix: "#".into(),
+                issue: number.into(),
+            };
+            if !commit.references.contains(&reference) {
+                commit.references.push(reference);
+            }
+            return Ok(commit);
+        }
         let s = self.regex_strip.replace(msg, "");
         let mut lines = s.lines();
         if let Some(first) = lines.next() {
@@ -197,6 +322,7 @@ pub struct CommitParserBuilder {
     scope_regex: String,
     references_regex: String,
     strip_regex: String,
+    squash_handling: SquashHandling,
 }
 
 impl Default for CommitParserBuilder {
@@ -211,6 +337,7 @@ impl CommitParserBuilder {
             scope_regex: "^[[:alnum:]]+(?:[-_/][[:alnum:]]+)*$".into(),
             references_regex: "(#)([0-9]+)".into(),
             strip_regex: "".into(),
+            squash_handling: SquashHandling::Off,
         }
     }
 
@@ -219,6 +346,7 @@ impl CommitParserBuilder {
             scope_regex,
             references_regex: self.references_regex,
             strip_regex: self.strip_regex,
+            squash_handling: self.squash_handling,
         }
     }
 
@@ -227,6 +355,7 @@ impl CommitParserBuilder {
             references_regex,
             scope_regex: self.scope_regex,
             strip_regex: self.strip_regex,
+            squash_handling: self.squash_handling,
         }
     }
 
@@ -235,6 +364,16 @@ impl CommitParserBuilder {
             strip_regex,
             references_regex: self.references_regex,
             scope_regex: self.scope_regex,
+            squash_handling: self.squash_handling,
+        }
+    }
+
+    pub fn squash_handling(self, squash_handling: SquashHandling) -> Self {
+        Self {
+            squash_handling,
+            scope_regex: self.scope_regex,
+            references_regex: self.references_regex,
+            strip_regex: self.strip_regex,
         }
     }
 
@@ -270,6 +409,7 @@ impl CommitParserBuilder {
             regex_footer,
             regex_references,
             regex_strip,
+            squash_handling: self.squash_handling,
         }
     }
 }
@@ -282,6 +422,71 @@ mod tests {
         CommitParser::builder().build()
     }
 
+    #[test]
+    fn squash_handling_expands_bullets_and_reads_pull_request_titles() {
+        let squash = "feat: x (#123)\n\n* feat(api): add endpoint\n\n* fix: crash #7\n\n* wip\n\nCo-authored-by: A <a@example.com>";
+        let off = parser().parse(squash).unwrap();
+        assert_eq!(parser().expand(off).len(), 1);
+
+        let expand = CommitParser::builder()
+            .squash_handling(SquashHandling::Expand)
+            .build();
+        let entries = expand.expand(expand.parse(squash).unwrap());
+        let pr = Reference {
+            action: None,
+            prefix: "#".into(),
+            issue: "123".into(),
+        };
+        assert_eq!(entries.len(), 2);
+        assert_eq!(entries[0].description, "add endpoint");
+        assert_eq!(entries[0].scope.as_deref(), Some("api"));
+        assert_eq!(entries[0].references, [pr]);
+        assert_eq!(entries[0].footers.len(), 1);
+        assert_eq!(entries[1].description, "crash #7");
+        assert_eq!(entries[1].references.len(), 2);
+        assert!(entries[1].footers.is_empty());
+
+        let breaking = "feat(api)!: drop v1 (#12)\n\n* fix: handle null\n\n* docs: update";
+        let entries = expand.expand(expand.parse(breaking).unwrap());
+        assert_eq!(entries.len(), 3);
+        assert!(entries[0].is_breaking());
+        assert_eq!(entries[0].description, "drop v1 (#12)");
+        assert!(!entries[1].is_breaking());
+        assert_eq!(entries[1].references[0].issue, "12");
+
+        let merge = "Merge pull request #42 from owner/branch\n\nfeat(api): title\n\nbody";
+        assert!(parser().parse(merge).is_err());
+        let pr_title = CommitParser::builder()
+            .squash_handling(SquashHandling::PrTitle)
+            .build();
+        let commit = pr_title.parse(merge).unwrap();
+        assert_eq!(commit.description, "title");
+        assert_eq!(commit.body.as_deref(), Some("body"));
+        assert_eq!(commit.references[0].issue, "42");
+    }
+
+    #[test]
+    fn revert_parses_git_and_conventional_reverts() {
+        let id = "0123456789abcdef0123456789abcdef01234567";
+        let git = Revert::parse(&format!(
+            "Revert \"feat: x\"\n\nThis reverts commit {id}.\n"
+        ))
+        .unwrap();
+        assert_eq!(git.subject, "feat: x");
+        assert_eq!(git.id.as_deref(), Some(id));
+        assert!(git.reverts(id));
+        assert!(git.reverts(&id[..7]));
+        assert!(!git.reverts("fedcba9"));
+
+        let conventional = Revert::parse("Revert(api)!: feat: x\n\nRefs #1").unwrap();
+        assert_eq!(conventional.subject, "feat: x");
+        assert_eq!(conventional.id, None);
+        assert!(!conventional.reverts(id));
+
+        assert_eq!(Revert::parse("feat: revert x"), None);
+        assert_eq!(Revert::parse("reverted: x"), None);
+    }
+
     #[test]
     fn test_simple() {
         let msg = "docs: correct spelling of CHANGELOG";
@@ -368,6 +573,17 @@ mod tests {
         assert!(conventional_commit.is_breaking());
     }
 
+    #[test]
+    fn test_is_footer() {
+        let parser = CommitParser::builder().build();
+        assert!(parser.is_footer("Reviewed-by: Z"));
+        assert!(parser.is_footer("BREAKING CHANGE: removed x"));
+        assert!(parser.is_footer("Closes #42"));
+        assert!(!parser.is_footer("Reviewed by: Z"));
+        assert!(!parser.is_footer("Reviewed-by:"));
+        assert!(!parser.is_footer("Refs: #1\nAcked-by: me"));
+    }
+
     #[test]
     fn test_with_breaking_footer() {
         let msg = "feat: allow provided config object to extend other configs\n\
@@ -3,10 +3,16 @@ use std::{
     path::{Path, PathBuf},
 };
 
+use regex::Regex;
 use semver::Version;
 use serde::{Deserialize, Deserializer, Serialize};
 use url::Url;
 
+use super::{
+    commit::{ConventionalCommit, Footer, SquashHandling},
+    lint::LintRules,
+    version_scheme::VersionScheme,
+};
 use crate::{error::ConvcoError, git::Repo};
 
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -17,6 +23,19 @@ pub enum Increment {
     None,
 }
 
+/// How the commits of a version are grouped in the changelog.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum GroupBy {
+    /// One section per type.
+    #[default]
+    Type,
+    /// One section per type, with a subsection per scope.
+    TypeScope,
+    /// One section per scope, with a subsection per type.
+    ScopeType,
+}
+
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct Type {
     pub r#type: String,
@@ -27,12 +46,26 @@ pub struct Type {
     pub hidden: bool,
 }
 
+impl fmt::Display for Increment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Major => write!(f, "major"),
+            Self::Minor => write!(f, "minor"),
+            Self::Patch => write!(f, "patch"),
+            Self::None => write!(f, "none"),
+        }
+    }
+}
+
 impl fmt::Display for Type {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(f, "{}", self.r#type)
     }
 }
 
+/// The footer of a commit that sets the next version, as in release-please.
+const RELEASE_AS: &str = "Release-As";
+
 pub fn commit_type_eq(config_type: &str, commit_type: &str) -> bool {
     config_type.eq_ignore_ascii_case(commit_type)
 }
@@ -44,7 +77,7 @@ pub fn commit_scope_eq(left: &str, right: &str) -> bool {
 /// see: [Conventional Changelog Configuration](https://github.com/conventional-changelog/conventional-changelog-config-spec/blob/master/versions/2.1.0/README.md)
 /// Additional config: `host`, `owner`, `repository`, `scope_regex` and `template`
 /// Those values are derived from `git remote origin get-url` if not set.
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 #[serde(rename_all = "camelCase")]
 pub struct Config {
     /// A string to be used as the main header section of the CHANGELOG.
@@ -79,15 +112,26 @@ pub struct Config {
     pub host: Option<String>,
     pub owner: Option<String>,
     pub repository: Option<String>,
+    /// `platform`. The hosting platform, for the syntax of the release notes.
+    /// Derived from `host` if not set.
+    pub platform: Option<Platform>,
     /// `template`. An optional template directory. The template should be called `template.hbs`. Partials can be used.
     pub template: Option<PathBuf>,
     /// `commitTemplate`. An optional template file for convco commit.
     pub commit_template: Option<PathBuf>,
+    /// `useEditor`. Open the editor at the end of the `convco commit` wizard.
+    /// When disabled the body and footers are prompted in the terminal.
+    #[serde(default = "default_true")]
+    pub use_editor: bool,
     /// `scopeRegex`. A regex to define possible scopes.
     /// For this project this could be `"changelog|check|commit|version"`.
     /// Defaults to `"^[[:alnum:]]+(?:[-_/][[:alnum:]]+)*$"`.
     #[serde(default = "default_scope_regex")]
     pub scope_regex: String,
+    /// `scopes`. The scopes of the project.
+    /// When set, `convco check` rejects other scopes and `convco commit` offers them to choose from.
+    #[serde(default)]
+    pub scopes: Vec<Scope>,
     /// Default number of characters in a single line of the CHANGELOG.
     /// This only makes sense if the template makes use of `{{#word-wrap}}` blocks.
     #[serde(default = "default_line_length")]
@@ -96,6 +140,19 @@ pub struct Config {
     /// This only makes sense if the template makes use of `{{#word-wrap}}` blocks.
     #[serde(default)]
     pub wrap_disabled: bool,
+    /// `groupBy`. Group the commits of the changelog by `type`, `typeScope` or `scopeType`.
+    #[serde(default)]
+    pub group_by: GroupBy,
+    /// `scopeOrder`. Order of the scope sections. Other scopes follow in alphabetical order.
+    #[serde(default)]
+    pub scope_order: Vec<String>,
+    /// `unscopedSection`. Title of the scope section of commits without a scope.
+    #[serde(default = "default_unscoped_section")]
+    pub unscoped_section: String,
+    /// `revertSection`. Title of the section of the reverts whose reverted commit is in another version.
+    /// A revert and the reverted commit in the same version are both left out.
+    #[serde(default = "default_revert_section")]
+    pub revert_section: String,
     /// Add link to compare 2 versions.
     #[serde(default = "default_true")]
     pub link_compare: bool,
@@ -108,6 +165,14 @@ pub struct Config {
     /// Follow only the first parent
     #[serde(default)]
     pub first_parent: bool,
+    /// `squashHandling`. Write the conventional bullets of squash commits as entries with `expand`,
+    /// or the pull request titles of merge commits with `prTitle`. `prTitle` includes merge commits,
+    /// best used with `firstParent`.
+    #[serde(default)]
+    pub squash_handling: SquashHandling,
+    /// `changelogFooters`. The footer tokens that leave out, rewrite or move the changelog entry of a commit.
+    #[serde(default)]
+    pub changelog_footers: ChangelogFooters,
     /// Strip the commit message(s) by the given regex pattern
     #[serde(default = "default_strip_regex")]
     pub strip_regex: String,
@@ -119,21 +184,277 @@ pub struct Config {
     /// Treat major version zero as stable when calculating the next version.
     #[serde(default)]
     pub treat_major_zero_as_stable: bool,
+    /// `versionScheme`. How versions are written in tags, `type: semver`, `calver` or `regex`.
+    #[serde(default)]
+    pub version_scheme: VersionScheme,
+    /// `tagPattern`. A regex with a `version` capture group, matched after the tag prefix.
+    /// Tags with the prefix that do not match are ignored.
+    pub tag_pattern: Option<String>,
+    /// `tagTemplate`. The tag of a new version after the prefix, `{{version}}` is replaced by the version.
+    pub tag_template: Option<String>,
     /// Ignore commits whose message matches any of the given regex patterns
     #[serde(default)]
     pub ignore_message_pattern: Vec<String>,
+    /// Lint rules checked by `convco check` and `convco commit`. All rules are off by default.
+    #[serde(default)]
+    pub rules: LintRules,
+    /// `packages`. The packages of a monorepo, used by `--all` of `convco version` and `convco changelog`.
+    #[serde(default)]
+    pub packages: Vec<Package>,
+    /// `bumpFiles`. Files updated with the new version by `convco version --write` and `convco release`.
+    #[serde(default)]
+    pub bump_files: Vec<BumpFile>,
+    /// `bumpRules`. Override the increment of the type for matching commits.
+    /// The first matching rule applies.
+    #[serde(default)]
+    pub bump_rules: Vec<BumpRule>,
+}
+
+/// Overrides the increment of commits that match all the conditions of the rule.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BumpRule {
+    /// `type`. The type of the commit.
+    pub r#type: Option<String>,
+    /// `scope`. The scope of the commit.
+    pub scope: Option<String>,
+    /// `footer`. The token of a footer of the commit.
+    pub footer: Option<String>,
+    /// `value`. The value of the footer, any value if not set.
+    pub value: Option<String>,
+    /// `breaking`. Only breaking changes match, and the increment applies to them.
+    #[serde(default)]
+    pub breaking: bool,
+    /// `increment`. The increment of matching commits, instead of the increment of the type.
+    /// Breaking changes keep their increment unless the rule sets `breaking`.
+    pub increment: Option<Increment>,
+    /// `releaseAs`. The value of the footer is the next version.
+    #[serde(default)]
+    pub release_as: bool,
+}
+
+impl BumpRule {
+    /// `true` if the commit matches all the conditions of the rule.
+    pub fn matches(&self, commit: &ConventionalCommit) -> bool {
+        self.r#type
+            .as_ref()
+            .is_none_or(|r#type| commit_type_eq(r#type, &commit.r#type))
+            && self.scope.as_ref().is_none_or(|scope| {
+                commit
+                    .scope
+                    .as_ref()
+                    .is_some_and(|commit_scope| commit_scope_eq(scope, commit_scope))
+            })
+            && (self.footer.is_none() || self.footer(commit).is_some())
+            && (!self.breaking || commit.is_breaking())
+    }
+
+    /// The increment of the rule for a matching commit, `None` for a breaking change
+    /// unless the rule is for breaking changes.
+    pub fn increment_of(&self, commit: &ConventionalCommit) -> Option<&Increment> {
+        self.increment
+            .as_ref()
+            .filter(|_| self.breaking || !commit.is_breaking())
+    }
+
+    /// The first footer of the commit matching `footer` and `value`.
+    pub fn footer<'a>(&self, commit: &'a ConventionalCommit) -> Option<&'a Footer> {
+        let token = self.footer.as_ref()?;
+        commit.footers.iter().find(|footer| {
+            footer.key.to_string().eq_ignore_ascii_case(token)
+                && self
+                    .value
+                    .as_ref()
+                    .is_none_or(|value| value.eq_ignore_ascii_case(footer.value.trim()))
+        })
+    }
+}
+
+impl fmt::Display for BumpRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut conditions = Vec::new();
+        if let Some(r#type) = &self.r#type {
+            conditions.push(format!("type {type}"));
+        }
+        if let Some(scope) = &self.scope {
+            conditions.push(format!("scope {scope}"));
+        }
+        match (&self.footer, &self.value) {
+            (Some(footer), Some(value)) => conditions.push(format!("footer {footer}: {value}")),
+            (Some(footer), None) => conditions.push(format!("footer {footer}")),
+            _ => {}
+        }
+        if self.breaking {
+            conditions.push("breaking".to_owned());
+        }
+        if conditions.is_empty() {
+            write!(f, "any commit")
+        } else {
+            write!(f, "{}", conditions.join(", "))
+        }
+    }
+}
+
+/// A file containing the version of the project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BumpFile {
+    /// `path`. Path of the file, relative to the root of the repository.
+    pub path: PathBuf,
+    /// `type`. How to update the file. Derived from the file name if not set:
+    /// `Cargo.toml`, `package.json`, `package-lock.json` and `pyproject.toml` are known,
+    /// any other file is a plain version file.
+    #[serde(default)]
+    pub r#type: Option<BumpFileType>,
+    /// `pattern`. A regex with a `version` capture group, for the `regex` type.
+    pub pattern: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum BumpFileType {
+    /// `version` of `[package]` or `[workspace.package]`, the workspace members and `Cargo.lock`.
+    Cargo,
+    /// The top level `version` of a `package.json`.
+    Npm,
+    /// The `version` of the package in a `package-lock.json`.
+    NpmLock,
+    /// `version` of `[project]` or `[tool.poetry]` in a `pyproject.toml`.
+    Python,
+    /// A file containing only the version.
+    Plain,
+    /// The `version` capture group of `pattern`.
+    Regex,
+}
+
+impl BumpFile {
+    pub fn file_type(&self) -> BumpFileType {
+        if let Some(r#type) = self.r#type {
+            return r#type;
+        }
+        if self.pattern.is_some() {
+            return BumpFileType::Regex;
+        }
+        match self.path.file_name().and_then(|name| name.to_str()) {
+            Some("Cargo.toml") => BumpFileType::Cargo,
+            Some("package.json") => BumpFileType::Npm,
+            Some("package-lock.json" | "npm-shrinkwrap.json") => BumpFileType::NpmLock,
+            Some("pyproject.toml") => BumpFileType::Python,
+            _ => BumpFileType::Plain,
+        }
+    }
+}
+
+/// A package of a monorepo with its own versions and changelog.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Package {
+    /// `name`. The name of the package.
+    pub name: String,
+    /// `paths`. Pathspecs of the package, relative to the root of the repository.
+    #[serde(default)]
+    pub paths: Vec<String>,
+    /// `tagPrefix`. Prefix of the version tags of the package. Defaults to `<name>-v`.
+    pub tag_prefix: Option<String>,
+    /// `changelog`. Path of the changelog of the package.
+    /// Defaults to `CHANGELOG.md` in the first path of the package.
+    pub changelog: Option<PathBuf>,
+    /// `bumpFiles`. Files containing the version of the package.
+    #[serde(default)]
+    pub bump_files: Vec<BumpFile>,
+}
+
+impl Package {
+    pub fn tag_prefix(&self) -> String {
+        self.tag_prefix
+            .clone()
+            .unwrap_or_else(|| format!("{}-v", self.name))
+    }
+
+    pub fn changelog(&self) -> PathBuf {
+        match (&self.changelog, self.paths.first()) {
+            (Some(changelog), _) => changelog.clone(),
+            (None, Some(path)) => Path::new(path).join("CHANGELOG.md"),
+            (None, None) => PathBuf::from("CHANGELOG.md"),
+        }
+    }
+}
+
+/// A code hosting platform that links issues, commits and mentions in release notes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Platform {
+    Github,
+    Gitlab,
+    Gitea,
+}
+
+/// A scope of the project.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Scope {
+    /// `name`. The scope as written in the changelog.
+    pub name: String,
+    /// `description`. Shown next to the name by `convco commit`.
+    pub description: Option<String>,
+    /// `aliases`. Other accepted spellings, replaced by `name` in the changelog.
+    #[serde(default)]
+    pub aliases: Vec<String>,
+    /// `paths`. Globs of the files of the scope, relative to the root of the repository.
+    /// `*` and `?` do not match `/`, `**` matches any number of directories.
+    /// A glob matching a directory matches all the files in it.
+    #[serde(default)]
+    pub paths: Vec<String>,
+}
+
+impl Scope {
+    /// `true` if `scope` is the name or one of the aliases.
+    pub fn is(&self, scope: &str) -> bool {
+        commit_scope_eq(&self.name, scope)
+            || self
+                .aliases
+                .iter()
+                .any(|alias| commit_scope_eq(alias, scope))
+    }
+
+    /// `true` if `path` matches one of the `paths` globs.
+    pub fn matches_path(&self, path: &str) -> bool {
+        self.paths
+            .iter()
+            .any(|glob| glob_regex(glob).is_match(path))
+    }
+}
+
+/// Translates a glob of [`Scope::paths`] to a regex.
+fn glob_regex(glob: &str) -> Regex {
+    let glob = glob.trim_start_matches('/').trim_end_matches('/');
+    let mut regex = String::from("^");
+    let mut rest = glob;
+    while let Some(c) = rest.chars().next() {
+        let (pattern, len) = match c {
+            '*' if rest.starts_with("**/") => ("(?:.*/)?".to_owned(), 3),
+            '*' if rest.starts_with("**") => (".*".to_owned(), 2),
+            '*' => ("[^/]*".to_owned(), 1),
+            '?' => ("[^/]".to_owned(), 1),
+            c => (regex::escape(c.encode_utf8(&mut [0; 4])), c.len_utf8()),
+        };
+        regex.push_str(&pattern);
+        rest = &rest[len..];
+    }
+    regex.push_str("(?:/.*)?$");
+    Regex::new(&regex).expect("escaped glob is a valid regex")
 }
 
 fn default_initial_bump_version() -> Version {
     Version::new(0, 1, 0)
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize, Default)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, Default)]
 pub struct DescriptionConfig {
     pub length: DescriptionLengthConfig,
 }
 
-#[derive(Debug, PartialEq, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct DescriptionLengthConfig {
     /// Define the minimum length of the description when using convco commit
     #[serde(default = "default_some_10")]
@@ -151,6 +472,53 @@ impl Default for DescriptionLengthConfig {
     }
 }
 
+/// The footers of a commit that change its changelog entry, without rewriting the history.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct ChangelogFooters {
+    /// `skip`. The commit is left out of the changelog when the value of this footer is `skip`.
+    pub skip: String,
+    /// `entry`. The value of this footer replaces the description in the changelog.
+    pub entry: String,
+    /// `section`. The value of this footer is the title of the section of the commit.
+    pub section: String,
+}
+
+impl Default for ChangelogFooters {
+    fn default() -> Self {
+        Self {
+            skip: "Changelog".into(),
+            entry: "Changelog-Entry".into(),
+            section: "Changelog-Section".into(),
+        }
+    }
+}
+
+impl ChangelogFooters {
+    fn value<'a>(footers: &'a [Footer], token: &str) -> Option<&'a str> {
+        footers
+            .iter()
+            .find(|footer| footer.key.to_string().eq_ignore_ascii_case(token))
+            .map(|footer| footer.value.trim())
+            .filter(|value| !value.is_empty())
+    }
+
+    /// Whether the footers leave the commit out of the changelog, e.g. `Changelog: skip`.
+    pub fn skip(&self, footers: &[Footer]) -> bool {
+        Self::value(footers, &self.skip).is_some_and(|value| value.eq_ignore_ascii_case("skip"))
+    }
+
+    /// The replacement of the description, e.g. `Changelog-Entry: support proxies`.
+    pub fn entry<'a>(&self, footers: &'a [Footer]) -> Option<&'a str> {
+        Self::value(footers, &self.entry)
+    }
+
+    /// The section of the commit, e.g. `Changelog-Section: Security`.
+    pub fn section<'a>(&self, footers: &'a [Footer]) -> Option<&'a str> {
+        Self::value(footers, &self.section)
+    }
+}
+
 fn deserialize_type<'de, D>(deserializer: D) -> Result<Vec<Type>, D::Error>
 where
     D: Deserializer<'de>,
@@ -212,23 +580,47 @@ impl Default for Config {
             host: None,
             owner: None,
             repository: None,
+            platform: None,
             template: None,
             commit_template: None,
+            use_editor: true,
             scope_regex: "^[[:alnum:]]+(?:[-_/][[:alnum:]]+)*$".to_string(),
+            scopes: vec![],
+            group_by: GroupBy::Type,
+            scope_order: vec![],
+            unscoped_section: "General".into(),
+            revert_section: "Reverts".into(),
             link_compare: true,
             link_references: true,
             merges: false,
             first_parent: false,
+            squash_handling: SquashHandling::Off,
+            changelog_footers: ChangelogFooters::default(),
             wrap_disabled: false,
             strip_regex: "".to_string(),
             description: Default::default(),
             initial_bump_version: Version::new(0, 1, 0),
             treat_major_zero_as_stable: false,
+            version_scheme: VersionScheme::Semver,
+            tag_pattern: None,
+            tag_template: None,
             ignore_message_pattern: vec![],
+            rules: LintRules::default(),
+            packages: vec![],
+            bump_files: vec![],
+            bump_rules: vec![],
         }
     }
 }
 
+fn default_unscoped_section() -> String {
+    "General".into()
+}
+
+fn default_revert_section() -> String {
+    "Reverts".into()
+}
+
 fn default_header() -> String {
     "# Changelog\n".into()
 }
@@ -373,6 +765,18 @@ fn host_info_from_url(url: Url) -> Result<HostOwnerRepo, ConvcoError> {
 }
 
 impl Config {
+    /// The footer that sets the next version: the footer of a `releaseAs` bump rule
+    /// if it is the first matching rule, otherwise the `Release-As` footer.
+    pub fn release_as<'a>(&self, commit: &'a ConventionalCommit) -> Option<&'a Footer> {
+        match self.bump_rules.iter().find(|rule| rule.matches(commit)) {
+            Some(rule) if rule.release_as => rule.footer(commit),
+            _ => commit
+                .footers
+                .iter()
+                .find(|footer| footer.key.to_string().eq_ignore_ascii_case(RELEASE_AS)),
+        }
+    }
+
     pub fn from_path(path: impl AsRef<Path>) -> Self {
         let mut config: Config = std::fs::read(path)
             .ok()
@@ -406,9 +810,44 @@ impl Config {
         }
 
         config.disable_reference_links_without_host();
+        config.check_bump_rules()?;
         Ok(config)
     }
 
+    /// Every `releaseAs` bump rule needs a `footer` with the version.
+    fn check_bump_rules(&self) -> Result<(), ConvcoError> {
+        match self
+            .bump_rules
+            .iter()
+            .position(|rule| rule.release_as && rule.footer.is_none())
+        {
+            Some(i) => Err(ConvcoError::ReleaseAsWithoutFooter(i + 1)),
+            None => Ok(()),
+        }
+    }
+
+    /// `platform`, or the platform known from the name of the `host`.
+    pub fn hosting_platform(&self) -> Option<Platform> {
+        if self.platform.is_some() {
+            return self.platform;
+        }
+        let host = self.host.as_deref()?;
+        if host.contains("github") {
+            Some(Platform::Github)
+        } else if host.contains("gitlab") {
+            Some(Platform::Gitlab)
+        } else if host.contains("gitea") || host.contains("codeberg") {
+            Some(Platform::Gitea)
+        } else {
+            None
+        }
+    }
+
+    /// The scope with `scope` as name or alias.
+    pub fn find_scope(&self, scope: &str) -> Option<&Scope> {
+        self.scopes.iter().find(|known| known.is(scope))
+    }
+
     fn disable_reference_links_without_host(&mut self) {
         if self.host.is_none() || self.commit_url_format.is_empty() {
             self.link_references = false;
@@ -419,6 +858,7 @@ impl Config {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::conventional::CommitParser;
 
     #[test]
     fn test_host_info_from_url() {
@@ -458,6 +898,185 @@ mod tests {
         );
     }
 
+    #[test]
+    fn package_defaults() {
+        let config: Config = serde_norway::from_str(
+            r#"
+            packages:
+              - name: app
+                paths: [packages/app, ":(exclude)packages/app/docs"]
+              - name: lib
+                tagPrefix: lib@
+                changelog: docs/LIB_CHANGELOG.md
+            "#,
+        )
+        .unwrap();
+        let [app, lib] = config.packages.as_slice() else {
+            panic!("expected two packages");
+        };
+        assert_eq!(app.tag_prefix(), "app-v");
+        assert_eq!(app.changelog(), Path::new("packages/app/CHANGELOG.md"));
+        assert_eq!(lib.tag_prefix(), "lib@");
+        assert_eq!(lib.changelog(), Path::new("docs/LIB_CHANGELOG.md"));
+    }
+
+    #[test]
+    fn hosting_platform_is_derived_from_the_host() {
+        let platform = |host: &str| {
+            Config {
+                host: Some(host.to_owned()),
+                ..Config::default()
+            }
+            .hosting_platform()
+        };
+        assert_eq!(platform("https://github.com"), Some(Platform::Github));
+        assert_eq!(
+            platform("https://gitlab.example.com"),
+            Some(Platform::Gitlab)
+        );
+        assert_eq!(platform("https://codeberg.org"), Some(Platform::Gitea));
+        assert_eq!(platform("https://git.example.com"), None);
+        let config = Config {
+            platform: Some(Platform::Gitea),
+            ..Config::default()
+        };
+        assert_eq!(config.hosting_platform(), Some(Platform::Gitea));
+    }
+
+    #[test]
+    fn scopes_match_aliases_and_paths() {
+        let config: Config = serde_norway::from_str(
+            r#"
+            scopes:
+              - name: cli
+                aliases: [CMD]
+                paths: [src/cmd, src/cli.rs]
+              - name: changelog
+                paths: ["src/**/changelog*", "*.hbs"]
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.find_scope("cmd").map(|s| s.name.as_str()),
+            Some("cli")
+        );
+        assert_eq!(
+            config.find_scope("CLI").map(|s| s.name.as_str()),
+            Some("cli")
+        );
+        assert_eq!(config.find_scope("git"), None);
+        let [cli, changelog] = config.scopes.as_slice() else {
+            panic!("expected two scopes");
+        };
+        assert!(cli.matches_path("src/cmd/check.rs"));
+        assert!(cli.matches_path("src/cli.rs"));
+        assert!(!cli.matches_path("src/cmd.rs"));
+        assert!(changelog.matches_path("src/changelog.rs"));
+        assert!(changelog.matches_path("src/conventional/changelog/template.hbs"));
+        assert!(changelog.matches_path("footer.hbs"));
+        assert!(!changelog.matches_path("src/conventional/footer.hbs"));
+    }
+
+    #[test]
+    fn bump_file_type_is_derived_from_the_file_name() {
+        let config: Config = serde_norway::from_str(
+            r#"
+            bumpFiles:
+              - path: Cargo.toml
+              - path: web/package.json
+              - path: web/package-lock.json
+              - path: pyproject.toml
+              - path: VERSION
+              - path: chart/Chart.yaml
+                pattern: 'appVersion: (?P<version>.+)'
+              - path: version.txt
+                type: regex
+                pattern: '(?P<version>.+)'
+            "#,
+        )
+        .unwrap();
+        let types: Vec<_> = config.bump_files.iter().map(BumpFile::file_type).collect();
+        assert_eq!(
+            types,
+            [
+                BumpFileType::Cargo,
+                BumpFileType::Npm,
+                BumpFileType::NpmLock,
+                BumpFileType::Python,
+                BumpFileType::Plain,
+                BumpFileType::Regex,
+                BumpFileType::Regex,
+            ]
+        );
+    }
+
+    #[test]
+    fn bump_rules_match_type_scope_and_footers() {
+        let config: Config = serde_norway::from_str(
+            r#"
+            bumpRules:
+              - type: fix
+                scope: api
+                increment: Minor
+              - footer: Bump
+                value: none
+                increment: None
+              - footer: Release-As
+                releaseAs: true
+            "#,
+        )
+        .unwrap();
+        let parser = CommitParser::builder().build();
+        let matched = |msg: &str| {
+            let commit = parser.parse(msg).unwrap();
+            config
+                .bump_rules
+                .iter()
+                .position(|rule| rule.matches(&commit))
+        };
+        assert_eq!(matched("fix(API): x"), Some(0));
+        assert_eq!(matched("fix: x"), None);
+        assert_eq!(matched("feat(api): x"), None);
+        assert_eq!(matched("feat: x\n\nbump: None"), Some(1));
+        assert_eq!(matched("feat: x\n\nBump: patch"), None);
+        assert_eq!(matched("feat: x\n\nRelease-As: 2.0.0"), Some(2));
+        let commit = parser.parse("feat: x\n\nRelease-As: 2.0.0").unwrap();
+        let footer = config.bump_rules[2].footer(&commit).unwrap();
+        assert_eq!(footer.value, "2.0.0");
+        assert_eq!(config.release_as(&commit), Some(footer));
+        let commit = parser.parse("fix: x\n\nrelease-as: 1.2.3").unwrap();
+        assert_eq!(config.release_as(&commit).unwrap().value, "1.2.3");
+        assert_eq!(config.bump_rules[0].to_string(), "type fix, scope api");
+        assert_eq!(config.bump_rules[1].to_string(), "footer Bump: none");
+        assert!(config.check_bump_rules().is_ok());
+        let commit = parser.parse("fix(api)!: x").unwrap();
+        assert!(config.bump_rules[0].matches(&commit));
+        assert_eq!(config.bump_rules[0].increment_of(&commit), None);
+
+        let rule: BumpRule =
+            serde_norway::from_str("{scope: deps, breaking: true, increment: Minor}").unwrap();
+        assert!(!rule.matches(&parser.parse("chore(deps): x").unwrap()));
+        let commit = parser.parse("chore(deps)!: x").unwrap();
+        assert!(rule.matches(&commit));
+        assert_eq!(rule.increment_of(&commit), Some(&Increment::Minor));
+        assert_eq!(rule.to_string(), "scope deps, breaking");
+
+        let config: Config = serde_norway::from_str(
+            r#"
+            bumpRules:
+              - scope: deps
+                increment: None
+              - type: feat
+                releaseAs: true
+            "#,
+        )
+        .unwrap();
+        assert!(matches!(
+            config.check_bump_rules(),
+            Err(ConvcoError::ReleaseAsWithoutFooter(2))
+        ));
+    }
+
     #[test]
     fn test() {
         let json = r#"{
@@ -571,19 +1190,39 @@ mod tests {
                 host: None,
                 owner: None,
                 repository: None,
+                platform: None,
                 template: None,
                 commit_template: None,
+                use_editor: true,
                 scope_regex: "^[[:alnum:]]+(?:[-_/][[:alnum:]]+)*$".to_string(),
+                scopes: vec![],
+                group_by: GroupBy::Type,
+                scope_order: vec![],
+                unscoped_section: "General".into(),
+                revert_section: "Reverts".into(),
                 link_compare: true,
                 link_references: true,
                 merges: false,
                 first_parent: false,
+                squash_handling: SquashHandling::Off,
+                changelog_footers: ChangelogFooters {
+                    skip: "Changelog".into(),
+                    entry: "Changelog-Entry".into(),
+                    section: "Changelog-Section".into(),
+                },
                 wrap_disabled: false,
                 strip_regex: "".to_string(),
                 description: DescriptionConfig { length: DescriptionLengthConfig { min: Some(10), max: None } },
                 initial_bump_version: Version::new(0, 1, 0),
                 treat_major_zero_as_stable: false,
+                version_scheme: VersionScheme::Semver,
+                tag_pattern: None,
+                tag_template: None,
                 ignore_message_pattern: vec![],
+                rules: LintRules::default(),
+                packages: vec![],
+                bump_files: vec![],
+                bump_rules: vec![],
             }
         )
     }
@@ -0,0 +1,319 @@
+//! Lint rules that run on top of a successfully parsed commit message.
+//!
+//! All rules are disabled by default and can be enabled in the `rules` section of `.convco`:
+//!
+//! ```yaml
+//! rules:
+//!   subjectCase: { level: error, case: lower }
+//!   headerMaxLength: { level: error, max: 72 }
+//!   subjectFullStop: { level: warning }
+//!   bodyMaxLineLength: { level: warning, max: 100 }
+//!   bodyLeadingBlank: { level: error }
+//!   footerTokens: { level: error, allowed: [Refs, Closes, Reviewed-by] }
+//! ```
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use super::commit::{ConventionalCommit, FooterKey};
+
+/// Severity of a rule. Only `error` makes a commit fail.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum RuleLevel {
+    #[default]
+    Off,
+    Warning,
+    Error,
+}
+
+impl fmt::Display for RuleLevel {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RuleLevel::Off => write!(f, "off"),
+            RuleLevel::Warning => write!(f, "warning"),
+            RuleLevel::Error => write!(f, "error"),
+        }
+    }
+}
+
+/// A rule without options.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Rule {
+    pub level: RuleLevel,
+}
+
+/// A rule limiting the number of characters in a line.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MaxLengthRule {
+    pub level: RuleLevel,
+    pub max: usize,
+}
+
+impl Default for MaxLengthRule {
+    fn default() -> Self {
+        Self {
+            level: RuleLevel::Off,
+            max: 100,
+        }
+    }
+}
+
+/// The expected case of the first letter of the description.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum SubjectCase {
+    #[default]
+    Lower,
+    Upper,
+}
+
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SubjectCaseRule {
+    pub level: RuleLevel,
+    pub case: SubjectCase,
+}
+
+/// Allow-list of footer tokens. `BREAKING CHANGE` is always allowed.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FooterTokensRule {
+    pub level: RuleLevel,
+    pub allowed: Vec<String>,
+}
+
+/// `rules`. Lint rules checked by `convco check` and `convco commit`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase", default)]
+pub struct LintRules {
+    /// `subjectCase`. Case of the first letter of the description.
+    pub subject_case: SubjectCaseRule,
+    /// `headerMaxLength`. Maximum number of characters of the first line.
+    pub header_max_length: MaxLengthRule,
+    /// `subjectFullStop`. The description must not end with a period.
+    pub subject_full_stop: Rule,
+    /// `bodyMaxLineLength`. Maximum number of characters of each line of the body.
+    pub body_max_line_length: MaxLengthRule,
+    /// `bodyLeadingBlank`. The body must begin one blank line after the description.
+    pub body_leading_blank: Rule,
+    /// `footerTokens`. Only the listed footer tokens are allowed.
+    pub footer_tokens: FooterTokensRule,
+}
+
+/// A rule that is not satisfied by a commit message.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Problem {
+    /// Stable identifier of the rule, e.g. `header-max-length`.
+    pub rule: &'static str,
+    pub level: RuleLevel,
+    pub message: String,
+    /// The offending line of the commit message.
+    pub line: String,
+}
+
+impl LintRules {
+    /// Checks `msg`, which was parsed into `commit`, against the enabled rules.
+    pub fn lint(&self, msg: &str, commit: &ConventionalCommit) -> Vec<Problem> {
+        let mut problems = Vec::new();
+        let mut lines = msg.lines();
+        let header = lines.next().unwrap_or_default();
+        let mut report = |rule, level, message: String, line: &str| {
+            if level != RuleLevel::Off {
+                problems.push(Problem {
+                    rule,
+                    level,
+                    message,
+                    line: line.to_owned(),
+                });
+            }
+        };
+
+        let level = self.subject_case.level;
+        if let Some(first) = commit.description.chars().next() {
+            let valid = match self.subject_case.case {
+                SubjectCase::Lower => !first.is_uppercase(),
+                SubjectCase::Upper => !first.is_lowercase(),
+            };
+            if !valid {
+                let case = match self.subject_case.case {
+                    SubjectCase::Lower => "lower",
+                    SubjectCase::Upper => "upper",
+                };
+                report(
+                    "subject-case",
+                    level,
+                    format!("description must start with a {case} case letter"),
+                    header,
+                );
+            }
+        }
+
+        let MaxLengthRule { level, max } = self.header_max_length;
+        let length = header.chars().count();
+        if length > max {
+            report(
+                "header-max-length",
+                level,
+                format!("header is longer than {max} characters ({length})"),
+                header,
+            );
+        }
+
+        if commit.description.trim_end().ends_with('.') {
+            report(
+                "subject-full-stop",
+                self.subject_full_stop.level,
+                "description must not end with a period".to_owned(),
+                header,
+            );
+        }
+
+        if let Some(second) = lines.next() {
+            if !second.trim().is_empty() {
+                report(
+                    "body-leading-blank",
+                    self.body_leading_blank.level,
+                    "body must begin one blank line after the description".to_owned(),
+                    second,
+                );
+            }
+        }
+
+        let MaxLengthRule { level, max } = self.body_max_line_length;
+        for line in commit.body.iter().flat_map(|body| body.lines()) {
+            let length = line.chars().count();
+            if length > max {
+                report(
+                    "body-max-line-length",
+                    level,
+                    format!("body line is longer than {max} characters ({length})"),
+                    line,
+                );
+            }
+        }
+
+        let FooterTokensRule { level, allowed } = &self.footer_tokens;
+        for footer in &commit.footers {
+            if let FooterKey::String(token) = &footer.key {
+                if !allowed.iter().any(|a| a.eq_ignore_ascii_case(token)) {
+                    report(
+                        "footer-tokens",
+                        *level,
+                        format!("footer token `{token}` is not allowed"),
+                        &format!("{token}: {}", footer.value.lines().next().unwrap_or("")),
+                    );
+                }
+            }
+        }
+
+        problems
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::CommitParser;
+
+    fn lint(rules: &LintRules, msg: &str) -> Vec<&'static str> {
+        let parser = CommitParser::builder().build();
+        let commit = parser.parse(msg).unwrap();
+        rules
+            .lint(msg, &commit)
+            .into_iter()
+            .map(|problem| problem.rule)
+            .collect()
+    }
+
+    fn all(level: RuleLevel) -> LintRules {
+        LintRules {
+            subject_case: SubjectCaseRule {
+                level,
+                case: SubjectCase::Lower,
+            },
+            header_max_length: MaxLengthRule { level, max: 30 },
+            subject_full_stop: Rule { level },
+            body_max_line_length: MaxLengthRule { level, max: 20 },
+            body_leading_blank: Rule { level },
+            footer_tokens: FooterTokensRule {
+                level,
+                allowed: vec!["Refs".into()],
+            },
+        }
+    }
+
+    #[test]
+    fn rules_are_off_by_default() {
+        let msg = "feat: Add a very long description that ends with a period.\nbody\n\nUnknown: x";
+        assert!(lint(&LintRules::default(), msg).is_empty());
+    }
+
+    #[test]
+    fn valid_message() {
+        let msg = "feat: add a feature\n\nshort body\n\nrefs: #1\nBREAKING CHANGE: yes";
+        assert!(lint(&all(RuleLevel::Error), msg).is_empty());
+    }
+
+    #[test]
+    fn reports_every_violated_rule() {
+        let msg = "feat: Add a very long description.\nthis line is way too long for the body\n\nAcked-by: me";
+        assert_eq!(
+            lint(&all(RuleLevel::Warning), msg),
+            vec![
+                "subject-case",
+                "header-max-length",
+                "subject-full-stop",
+                "body-leading-blank",
+                "body-max-line-length",
+                "footer-tokens",
+            ]
+        );
+    }
+
+    #[test]
+    fn subject_case_upper() {
+        let rules = LintRules {
+            subject_case: SubjectCaseRule {
+                level: RuleLevel::Error,
+                case: SubjectCase::Upper,
+            },
+            ..Default::default()
+        };
+        assert_eq!(lint(&rules, "fix: lower"), vec!["subject-case"]);
+        assert!(lint(&rules, "fix: Upper").is_empty());
+        assert!(lint(&rules, "fix: 42 things").is_empty());
+    }
+
+    #[test]
+    fn deserialize() {
+        let rules: LintRules = serde_norway::from_str(
+            r#"
+            headerMaxLength: { level: error, max: 72 }
+            subjectFullStop: { level: warning }
+            footerTokens: { level: error, allowed: [Refs] }
+            "#,
+        )
+        .unwrap();
+        assert_eq!(
+            rules,
+            LintRules {
+                header_max_length: MaxLengthRule {
+                    level: RuleLevel::Error,
+                    max: 72
+                },
+                subject_full_stop: Rule {
+                    level: RuleLevel::Warning
+                },
+                footer_tokens: FooterTokensRule {
+                    level: RuleLevel::Error,
+                    allowed: vec!["Refs".into()]
+                },
+                ..Default::default()
+            }
+        );
+    }
+}
@@ -22,6 +22,8 @@
 pub mod changelog;
 pub(crate) mod commit;
 pub(crate) mod config;
+pub(crate) mod lint;
+pub(crate) mod version_scheme;
 
-pub use commit::{CommitParser, ParseError};
+pub use commit::{CommitParser, ParseError, Revert, SquashHandling};
 pub use config::Config;
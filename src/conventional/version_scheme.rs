@@ -0,0 +1,606 @@
+//! Versions other than semantic versions, such as calendar versions.
+//!
+//! Every version is mapped to a [`Version`], so tags are ordered and compared the same way for
+//! every scheme: the components of a calendar version or the numbers of a regex version are the
+//! major, minor and patch.
+
+use std::fmt;
+
+use jiff::civil::Date;
+use regex::Regex;
+use semver::{BuildMetadata, Prerelease, Version};
+use serde::{Deserialize, Serialize};
+
+use super::config::{Config, Increment};
+use crate::error::ConvcoError;
+
+/// `versionScheme`. How versions are written in tags, selected by `type`.
+#[derive(Debug, Default, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "camelCase")]
+pub enum VersionScheme {
+    /// Semantic versions, `1.2.3`.
+    #[default]
+    Semver,
+    /// Calendar versions.
+    Calver {
+        /// `format`. Components separated by dots, like `YYYY.MM.MICRO`, see [`CalverToken`].
+        format: String,
+    },
+    /// Versions matched by a regex.
+    Regex {
+        /// `pattern`. The regex that matches the version, without the tag prefix.
+        pattern: String,
+        /// `comparator`. How the matched versions are compared.
+        #[serde(default)]
+        comparator: Comparator,
+    },
+}
+
+/// How the versions of a [`VersionScheme::Regex`] are compared.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub enum Comparator {
+    /// The `major`, `minor` and `patch` capture groups, or the first three groups, as numbers.
+    /// A `pre` capture group is the pre-release.
+    #[default]
+    Numeric,
+    /// The `version` capture group, or the whole match, as a semantic version.
+    Semver,
+}
+
+/// A component of a calendar version, see <https://calver.org>.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CalverToken {
+    /// `YYYY`. Full year, `2006`.
+    FullYear,
+    /// `YY`. Short year, `6`, `16`, `106`.
+    ShortYear,
+    /// `0Y`. Zero-padded short year, `06`, `16`, `106`.
+    ZeroPaddedYear,
+    /// `MM`. Month, `1` to `12`.
+    Month,
+    /// `0M`. Zero-padded month, `01` to `12`.
+    ZeroPaddedMonth,
+    /// `WW`. ISO week, `1` to `53`.
+    Week,
+    /// `0W`. Zero-padded ISO week, `01` to `53`.
+    ZeroPaddedWeek,
+    /// `DD`. Day, `1` to `31`.
+    Day,
+    /// `0D`. Zero-padded day, `01` to `31`.
+    ZeroPaddedDay,
+    /// `MAJOR`. Counter incremented by breaking changes.
+    Major,
+    /// `MINOR`. Counter incremented by features.
+    Minor,
+    /// `MICRO`. Counter incremented by any other release.
+    Micro,
+}
+
+impl CalverToken {
+    fn parse(token: &str) -> Option<Self> {
+        Some(match token {
+            "YYYY" => Self::FullYear,
+            "YY" => Self::ShortYear,
+            "0Y" => Self::ZeroPaddedYear,
+            "MM" => Self::Month,
+            "0M" => Self::ZeroPaddedMonth,
+            "WW" => Self::Week,
+            "0W" => Self::ZeroPaddedWeek,
+            "DD" => Self::Day,
+            "0D" => Self::ZeroPaddedDay,
+            "MAJOR" => Self::Major,
+            "MINOR" => Self::Minor,
+            "MICRO" => Self::Micro,
+            _ => return None,
+        })
+    }
+
+    fn is_counter(self) -> bool {
+        matches!(self, Self::Major | Self::Minor | Self::Micro)
+    }
+
+    fn is_zero_padded(self) -> bool {
+        matches!(
+            self,
+            Self::ZeroPaddedYear
+                | Self::ZeroPaddedMonth
+                | Self::ZeroPaddedWeek
+                | Self::ZeroPaddedDay
+        )
+    }
+
+    /// The value of a date component on `date`, `None` for counters.
+    fn value(self, date: Date) -> Option<u64> {
+        let value = match self {
+            Self::FullYear => i64::from(date.year()),
+            Self::ShortYear | Self::ZeroPaddedYear => i64::from(date.year()) - 2000,
+            Self::Month | Self::ZeroPaddedMonth => i64::from(date.month()),
+            Self::Week | Self::ZeroPaddedWeek => i64::from(date.iso_week_date().week()),
+            Self::Day | Self::ZeroPaddedDay => i64::from(date.day()),
+            Self::Major | Self::Minor | Self::Micro => return None,
+        };
+        u64::try_from(value).ok()
+    }
+}
+
+impl VersionScheme {
+    /// Validates the scheme and compiles it to read and write versions.
+    pub fn compile(&self) -> Result<VersionFormat, ConvcoError> {
+        let invalid = |reason: String| ConvcoError::VersionScheme(reason);
+        Ok(match self {
+            Self::Semver => VersionFormat::Semver,
+            Self::Calver { format } => {
+                let tokens = format
+                    .split('.')
+                    .map(|token| {
+                        CalverToken::parse(token).ok_or_else(|| {
+                            invalid(format!("unknown calver token `{token}` in `{format}`"))
+                        })
+                    })
+                    .collect::<Result<Vec<_>, _>>()?;
+                if tokens.len() > 3 {
+                    return Err(invalid(format!(
+                        "calver format `{format}` has more than three components"
+                    )));
+                }
+                VersionFormat::Calver(tokens)
+            }
+            Self::Regex {
+                pattern,
+                comparator,
+            } => VersionFormat::Regex(Regex::new(pattern)?, *comparator),
+        })
+    }
+}
+
+/// A validated [`VersionScheme`].
+#[derive(Debug, Clone)]
+pub enum VersionFormat {
+    Semver,
+    Calver(Vec<CalverToken>),
+    Regex(Regex, Comparator),
+}
+
+impl VersionFormat {
+    /// Reads the version of a tag without its prefix, `None` if it is not a version of the scheme.
+    pub fn parse(&self, version: &str) -> Option<Version> {
+        match self {
+            Self::Semver => Version::parse(version).ok(),
+            Self::Calver(tokens) => {
+                let (version, pre) = match version.split_once('-') {
+                    Some((version, pre)) => (version, Prerelease::new(pre).ok()?),
+                    None => (version, Prerelease::EMPTY),
+                };
+                let components = version
+                    .split('.')
+                    .map(|component| {
+                        component
+                            .bytes()
+                            .all(|b| b.is_ascii_digit())
+                            .then(|| component.parse::<u64>().ok())
+                            .flatten()
+                    })
+                    .collect::<Option<Vec<_>>>()?;
+                if components.len() != tokens.len() {
+                    return None;
+                }
+                let mut version = from_components(&components);
+                version.pre = pre;
+                Some(version)
+            }
+            Self::Regex(regex, Comparator::Semver) => {
+                let captures = regex.captures(version)?;
+                let version = captures.name("version").or_else(|| captures.get(0))?;
+                Version::parse(version.as_str()).ok()
+            }
+            Self::Regex(regex, Comparator::Numeric) => {
+                let captures = regex.captures(version)?;
+                let names = ["major", "minor", "patch"];
+                let components = if names.iter().any(|name| captures.name(name).is_some()) {
+                    names
+                        .iter()
+                        .map(|name| {
+                            captures
+                                .name(name)
+                                .map_or(Some(0), |m| m.as_str().parse().ok())
+                        })
+                        .collect::<Option<Vec<_>>>()?
+                } else {
+                    captures
+                        .iter()
+                        .skip(1)
+                        .flatten()
+                        .take(3)
+                        .map(|m| m.as_str().parse().ok())
+                        .collect::<Option<Vec<_>>>()?
+                };
+                let mut version = from_components(&components);
+                if let Some(pre) = captures.name("pre") {
+                    version.pre = Prerelease::new(pre.as_str()).ok()?;
+                }
+                Some(version)
+            }
+        }
+    }
+
+    /// Writes `version` as it is tagged, without the prefix.
+    /// The numbers of a [`Comparator::Numeric`] regex version are joined by dots,
+    /// as many as the regex has groups or more if a bump set a later number.
+    pub fn format(&self, version: &Version) -> String {
+        let components = [version.major, version.minor, version.patch];
+        let mut string = match self {
+            Self::Semver | Self::Regex(_, Comparator::Semver) => return version.to_string(),
+            Self::Calver(tokens) => tokens
+                .iter()
+                .zip(components)
+                .map(|(token, value)| match token.is_zero_padded() {
+                    true => format!("{value:02}"),
+                    false => value.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join("."),
+            Self::Regex(regex, Comparator::Numeric) => {
+                let count = ["major", "minor", "patch"]
+                    .iter()
+                    .filter(|name| regex.capture_names().flatten().any(|n| n == **name))
+                    .count();
+                let count = match count {
+                    0 => (regex.captures_len() - 1).clamp(1, 3),
+                    count => count,
+                };
+                let count = count.max(3 - components.iter().rev().take_while(|c| **c == 0).count());
+                components[..count]
+                    .iter()
+                    .map(u64::to_string)
+                    .collect::<Vec<_>>()
+                    .join(".")
+            }
+        };
+        if !version.pre.is_empty() {
+            string.push('-');
+            string.push_str(version.pre.as_str());
+        }
+        string
+    }
+
+    /// `true` for calendar versions, which are bumped to the date of the release.
+    pub fn is_calendar(&self) -> bool {
+        matches!(self, Self::Calver(_))
+    }
+
+    /// The calendar version released on `date` after `last`.
+    ///
+    /// The date components are set to `date`. When they are the same as in `last`, the counter
+    /// of the `increment` is incremented: `MAJOR`, `MINOR` or `MICRO`, or the last counter
+    /// of the format. Otherwise the counters after the first date component restart at zero.
+    pub fn calendar_version(
+        &self,
+        last: Option<&Version>,
+        increment: &Increment,
+        date: Date,
+    ) -> Version {
+        let Self::Calver(tokens) = self else {
+            return last.cloned().unwrap_or_else(|| Version::new(0, 0, 0));
+        };
+        let last = last.map_or([0; 3], |last| [last.major, last.minor, last.patch]);
+        let mut components = last;
+        for (component, token) in components.iter_mut().zip(tokens) {
+            if let Some(value) = token.value(date) {
+                *component = value;
+            }
+        }
+        let date_changed = components != last;
+        if date_changed {
+            let first_date = tokens.iter().position(|token| !token.is_counter());
+            for (i, token) in tokens.iter().enumerate() {
+                if token.is_counter() && first_date.is_some_and(|first| i > first) {
+                    components[i] = 0;
+                }
+            }
+        } else {
+            let counter = match increment {
+                Increment::Major => Some(CalverToken::Major),
+                Increment::Minor => Some(CalverToken::Minor),
+                Increment::Patch => Some(CalverToken::Micro),
+                Increment::None => None,
+            };
+            let position = counter.and_then(|counter| {
+                tokens
+                    .iter()
+                    .position(|token| *token == counter)
+                    .or_else(|| tokens.iter().rposition(|token| token.is_counter()))
+            });
+            if let Some(position) = position {
+                components[position] += 1;
+                for (i, token) in tokens.iter().enumerate().skip(position + 1) {
+                    if token.is_counter() {
+                        components[i] = 0;
+                    }
+                }
+            }
+        }
+        let mut version = from_components(&components);
+        version.build = BuildMetadata::EMPTY;
+        version
+    }
+}
+
+/// How versions are tagged: the prefix, the `tagPattern`, the `tagTemplate` and the scheme.
+#[derive(Debug, Clone)]
+pub struct TagFormat {
+    prefix: String,
+    pattern: Option<Regex>,
+    template: Option<String>,
+    versions: VersionFormat,
+}
+
+/// Why a tag with the prefix is not a version tag.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TagMismatch {
+    /// The tag does not match the `tagPattern`.
+    Pattern,
+    /// The matched version is not a version of the `versionScheme`.
+    Version(String),
+}
+
+impl fmt::Display for TagMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pattern => write!(f, "does not match the tagPattern"),
+            Self::Version(version) => {
+                write!(f, "`{version}` is not a version of the versionScheme")
+            }
+        }
+    }
+}
+
+impl TagFormat {
+    /// The tags starting with `prefix`, read with the `tagPattern`, `tagTemplate` and
+    /// `versionScheme` of `config`.
+    pub fn new(prefix: &str, config: &Config) -> Result<Self, ConvcoError> {
+        let pattern = match &config.tag_pattern {
+            Some(pattern) => {
+                let regex = Regex::new(pattern)?;
+                if !regex
+                    .capture_names()
+                    .flatten()
+                    .any(|name| name == "version")
+                {
+                    return Err(ConvcoError::TagPatternWithoutVersion(pattern.clone()));
+                }
+                if config.tag_template.is_none() {
+                    return Err(ConvcoError::TagPatternWithoutTemplate(pattern.clone()));
+                }
+                Some(regex)
+            }
+            None => None,
+        };
+        Ok(Self {
+            prefix: prefix.to_owned(),
+            pattern,
+            template: config.tag_template.clone(),
+            versions: config.version_scheme.compile()?,
+        })
+    }
+
+    pub fn prefix(&self) -> &str {
+        &self.prefix
+    }
+
+    pub fn versions(&self) -> &VersionFormat {
+        &self.versions
+    }
+
+    /// Reads the version of a tag starting with the prefix.
+    /// The `tagPattern` is matched after the prefix, its `version` group is the version.
+    pub fn version(&self, tag: &str) -> Result<Version, TagMismatch> {
+        let rest = tag
+            .strip_prefix(self.prefix.as_str())
+            .ok_or(TagMismatch::Pattern)?;
+        let version = match &self.pattern {
+            Some(pattern) => pattern
+                .captures(rest)
+                .and_then(|captures| captures.name("version"))
+                .ok_or(TagMismatch::Pattern)?
+                .as_str(),
+            None => rest,
+        };
+        self.versions
+            .parse(version)
+            .ok_or_else(|| TagMismatch::Version(version.to_owned()))
+    }
+
+    /// The tag of `version`: the prefix and the `tagTemplate`, with `{{version}}` replaced by
+    /// the version and `{{major}}`, `{{minor}}` and `{{patch}}` by its numbers.
+    pub fn tag(&self, version: &Version) -> String {
+        let formatted = self.versions.format(version);
+        match &self.template {
+            Some(template) => format!(
+                "{}{}",
+                self.prefix,
+                template
+                    .replace("{{version}}", &formatted)
+                    .replace("{{major}}", &version.major.to_string())
+                    .replace("{{minor}}", &version.minor.to_string())
+                    .replace("{{patch}}", &version.patch.to_string())
+            ),
+            None => format!("{}{formatted}", self.prefix),
+        }
+    }
+
+    /// The tag of a new `version`. It must be read back as `version`,
+    /// otherwise the next run would ignore it.
+    pub fn new_tag(&self, version: &Version) -> Result<String, ConvcoError> {
+        let tag = self.tag(version);
+        match self.version(&tag) {
+            Ok(read) if read == *version => Ok(tag),
+            _ => Err(ConvcoError::UnreadableTag {
+                tag,
+                version: self.versions.format(version),
+            }),
+        }
+    }
+}
+
+fn from_components(components: &[u64]) -> Version {
+    let component = |i: usize| components.get(i).copied().unwrap_or_default();
+    Version::new(component(0), component(1), component(2))
+}
+
+#[cfg(test)]
+mod tests {
+    use jiff::civil::date;
+
+    use super::*;
+
+    fn calver(format: &str) -> VersionFormat {
+        VersionScheme::Calver {
+            format: format.into(),
+        }
+        .compile()
+        .unwrap()
+    }
+
+    #[test]
+    fn calver_versions_are_read_and_written() {
+        let format = calver("YYYY.0M.MICRO");
+        let version = format.parse("2026.04.3").unwrap();
+        assert_eq!(version, Version::new(2026, 4, 3));
+        assert_eq!(format.format(&version), "2026.04.3");
+        assert_eq!(
+            format.format(&format.parse("2026.04.3-rc.1").unwrap()),
+            "2026.04.3-rc.1"
+        );
+        assert_eq!(format.parse("2026.04"), None);
+        assert_eq!(format.parse("v2026.04.1"), None);
+
+        let format = calver("YYYY.WW");
+        assert_eq!(format.parse("2026.42"), Some(Version::new(2026, 42, 0)));
+        assert!(format.parse("2026.9") < format.parse("2026.42"));
+
+        assert!(VersionScheme::Calver {
+            format: "YYYY.QQ".into()
+        }
+        .compile()
+        .is_err());
+    }
+
+    #[test]
+    fn calver_versions_are_bumped_to_the_date() {
+        let format = calver("YYYY.MM.MICRO");
+        let today = date(2026, 10, 16);
+        let last = Version::new(2026, 9, 4);
+        assert_eq!(
+            format.calendar_version(Some(&last), &Increment::Patch, today),
+            Version::new(2026, 10, 0)
+        );
+        let last = Version::new(2026, 10, 0);
+        assert_eq!(
+            format.calendar_version(Some(&last), &Increment::Minor, today),
+            Version::new(2026, 10, 1)
+        );
+        assert_eq!(
+            format.calendar_version(None, &Increment::Patch, today),
+            Version::new(2026, 10, 0)
+        );
+
+        let format = calver("YY.MINOR.MICRO");
+        let last = Version::new(26, 2, 5);
+        assert_eq!(
+            format.calendar_version(Some(&last), &Increment::Minor, today),
+            Version::new(26, 3, 0)
+        );
+    }
+
+    #[test]
+    fn tags_are_read_with_the_pattern_and_written_with_the_template() {
+        let config = Config {
+            tag_pattern: Some(r"^(?<version>.+)-final$".into()),
+            tag_template: Some("{{version}}-final".into()),
+            ..Config::default()
+        };
+        let tags = TagFormat::new("release-", &config).unwrap();
+        assert_eq!(
+            tags.version("release-1.2.3-final"),
+            Ok(Version::new(1, 2, 3))
+        );
+        assert_eq!(tags.version("release-1.2.3"), Err(TagMismatch::Pattern));
+        assert_eq!(
+            tags.version("release-one-final"),
+            Err(TagMismatch::Version("one".into()))
+        );
+        assert_eq!(tags.tag(&Version::new(1, 3, 0)), "release-1.3.0-final");
+
+        let config = Config {
+            tag_pattern: Some(r"^\d+$".into()),
+            ..Config::default()
+        };
+        assert!(TagFormat::new("v", &config).is_err());
+        let config = Config {
+            tag_pattern: Some(r"^(?<version>.+)-final$".into()),
+            ..Config::default()
+        };
+        assert!(TagFormat::new("v", &config).is_err());
+    }
+
+    #[test]
+    fn new_tags_are_read_back_as_their_version() {
+        let config = Config {
+            version_scheme: VersionScheme::Regex {
+                pattern: r"^r(\d+)-(\d+)$".into(),
+                comparator: Comparator::Numeric,
+            },
+            ..Config::default()
+        };
+        let tags = TagFormat::new("", &config).unwrap();
+        assert!(tags.new_tag(&Version::new(1, 2, 0)).is_err());
+
+        let config = Config {
+            version_scheme: VersionScheme::Regex {
+                pattern: r"^(\d+)\.(\d+)$".into(),
+                comparator: Comparator::Numeric,
+            },
+            ..Config::default()
+        };
+        let tags = TagFormat::new("", &config).unwrap();
+        assert_eq!(tags.new_tag(&Version::new(1, 2, 0)).unwrap(), "1.2");
+        assert!(tags.new_tag(&Version::new(1, 2, 1)).is_err());
+
+        let config = Config {
+            version_scheme: VersionScheme::Regex {
+                pattern: r"^r(\d+)-(\d+)$".into(),
+                comparator: Comparator::Numeric,
+            },
+            tag_template: Some("r{{major}}-{{minor}}".into()),
+            ..Config::default()
+        };
+        let tags = TagFormat::new("", &config).unwrap();
+        assert_eq!(tags.new_tag(&Version::new(1, 2, 0)).unwrap(), "r1-2");
+        assert!(tags.new_tag(&Version::new(1, 2, 1)).is_err());
+    }
+
+    #[test]
+    fn regex_versions_are_compared_by_numbers() {
+        let format = VersionScheme::Regex {
+            pattern: r"^r(\d+)-(\d+)$".into(),
+            comparator: Comparator::Numeric,
+        }
+        .compile()
+        .unwrap();
+        let version = format.parse("r12-3").unwrap();
+        assert_eq!(version, Version::new(12, 3, 0));
+        assert!(format.parse("r9-10") < format.parse("r12-3"));
+        assert_eq!(format.format(&version), "12.3");
+        assert_eq!(format.format(&Version::new(12, 3, 1)), "12.3.1");
+
+        let format = VersionScheme::Regex {
+            pattern: r"^release-(?<version>.+)$".into(),
+            comparator: Comparator::Semver,
+        }
+        .compile()
+        .unwrap();
+        assert_eq!(format.parse("release-1.2.3"), Some(Version::new(1, 2, 3)));
+        assert_eq!(format.parse("1.2.3"), None);
+    }
+}
@@ -45,6 +45,9 @@ pub enum ConvcoError {
     #[cfg(feature = "gix")]
     #[error(transparent)]
     GixObjectDecodeError(Box<gix::objs::decode::Error>),
+    #[cfg(feature = "gix")]
+    #[error(transparent)]
+    GixMailmapLoadError(Box<gix::mailmap::load::Error>),
     #[error(transparent)]
     Io(#[from] io::Error),
     #[error(transparent)]
@@ -78,6 +81,43 @@ pub enum ConvcoError {
     CancelledByUser,
     #[error("git commit failed: {0}")]
     GitCommitFailed(ExitStatus),
+    #[error("no packages are configured, add a `packages` section to the configuration")]
+    NoPackages,
+    #[error("git {command} failed: {status}")]
+    GitFailed {
+        command: &'static str,
+        status: ExitStatus,
+    },
+    #[error("nothing to release, no commits since {0}")]
+    NothingToRelease(String),
+    #[error("tag {0} already exists")]
+    TagExists(String),
+    #[error("--incremental requires an --output file")]
+    IncrementalWithoutOutput,
+    #[error("invalid versionScheme: {0}")]
+    VersionScheme(String),
+    #[error("tagPattern `{0}` has no `version` capture group")]
+    TagPatternWithoutVersion(String),
+    #[error("tagPattern `{0}` requires a tagTemplate to write the tags of new versions")]
+    TagPatternWithoutTemplate(String),
+    #[error("the tag `{tag}` of version {version} does not match the tagPattern and versionScheme, set a tagTemplate that does")]
+    UnreadableTag { tag: String, version: String },
+    #[error("no version heading found in {0}")]
+    NoVersionHeading(String),
+    #[error("bump rule {0} has releaseAs but no footer to read the version from")]
+    ReleaseAsWithoutFooter(usize),
+    #[error("`{value}` of the {footer} footer is not a version")]
+    ReleaseAs { footer: String, value: String },
+    #[error("{footer} {version} does not move forward from {last}")]
+    ReleaseAsNotForward {
+        footer: String,
+        version: String,
+        last: String,
+    },
+    #[error("invalid date `{0}`, expected `YYYY-MM-DD` or an RFC 3339 timestamp")]
+    InvalidDate(String),
+    #[error("could not bump {path}: {reason}")]
+    BumpFile { path: String, reason: String },
 }
 
 #[cfg(feature = "gix")]
@@ -122,3 +162,5 @@ impl_boxed_from!(gix::object::peel::to_kind::Error, Gix);
 impl_boxed_from!(gix::object::commit::Error, GixCommitError);
 #[cfg(feature = "gix")]
 impl_boxed_from!(gix::objs::decode::Error, GixObjectDecodeError);
+#[cfg(feature = "gix")]
+impl_boxed_from!(gix::mailmap::load::Error, GixMailmapLoadError);
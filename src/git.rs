@@ -1,7 +1,18 @@
-use std::{borrow::Cow, fmt::Debug};
+use std::{
+    borrow::Cow,
+    collections::{HashMap, HashSet},
+    fmt::Debug,
+    path::PathBuf,
+};
+
+use regex::Regex;
 
 use crate::{
-    conventional::commit::{CommitParser, ConventionalCommit},
+    conventional::{
+        commit::{CommitParser, ConventionalCommit},
+        version_scheme::{TagFormat, TagMismatch},
+        Revert,
+    },
     error::ConvcoError,
 };
 
@@ -26,13 +37,45 @@ pub struct Commit<C> {
     pub commit: C,
 }
 
+/// The tags of a [`TagFormat`], see [`Repo::version_tags`].
+#[derive(Debug)]
+pub struct VersionTags<C> {
+    /// The version tags, the highest version first.
+    pub versions: Vec<(semver::Version, C)>,
+    /// The name of the tag of every version. A tag name is not always the one
+    /// [`TagFormat::tag`] writes, e.g. for unpadded calver numbers.
+    pub names: HashMap<semver::Version, String>,
+    /// The tags with the prefix that are not version tags, and why.
+    pub ignored: Vec<(String, TagMismatch)>,
+}
+
+/// The name and email of the author or the committer of a commit.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Signature {
+    pub name: String,
+    pub email: String,
+}
+
 pub trait CommitTrait: Debug + Clone {
     type ObjectId;
+    /// The mailmap of the repository, see [`Repo::mailmap`].
+    type Mailmap;
     fn short_id(&self) -> String;
     fn id(&self) -> String;
     fn oid(&self) -> Self::ObjectId;
     fn commit_message(&self) -> Result<Cow<'_, str>, ConvcoError>;
     fn commit_time(&self) -> Result<jiff::Zoned, ConvcoError>;
+    fn author(&self) -> Result<Signature, ConvcoError>;
+    fn committer(&self) -> Result<Signature, ConvcoError>;
+    /// The author with the canonical name and email of the mailmap.
+    fn author_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError>;
+    /// The committer with the canonical name and email of the mailmap.
+    fn committer_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError>;
+    /// Any signature, e.g. of a `Co-authored-by` trailer, with the canonical name and email of the mailmap.
+    fn signature_with_mailmap(
+        mailmap: &Self::Mailmap,
+        signature: Signature,
+    ) -> Result<Signature, ConvcoError>;
 }
 
 pub type RevWalkIter<'repo, C> =
@@ -54,14 +97,45 @@ pub trait Repo<'repo>: Sized {
         options: RevWalkOptions<'repo, Self::CommitTrait>,
     ) -> Result<RevWalkIter<'repo, Self::CommitTrait>, ConvcoError>;
 
-    /// Get the list of tags matching the prefix ordered by semver
+    /// The names of the tags starting with `prefix` and the commits they point to.
+    fn tags(&'repo self, prefix: &str) -> Result<Vec<(String, Self::CommitTrait)>, ConvcoError>;
+
+    /// The tags with the prefix of `format`, read as versions.
+    fn version_tags(
+        &'repo self,
+        format: &TagFormat,
+    ) -> Result<VersionTags<Self::CommitTrait>, ConvcoError> {
+        let mut tags = VersionTags {
+            versions: Vec::new(),
+            names: HashMap::new(),
+            ignored: Vec::new(),
+        };
+        for (name, commit) in self.tags(format.prefix())? {
+            match format.version(&name) {
+                Ok(version) => {
+                    tags.names.entry(version.clone()).or_insert(name);
+                    tags.versions.push((version, commit));
+                }
+                Err(mismatch) => tags.ignored.push((name, mismatch)),
+            }
+        }
+        tags.versions.sort_by(|a, b| b.0.cmp(&a.0));
+        Ok(tags)
+    }
+
+    /// Get the list of version tags ordered by version, the highest first.
     fn semver_tags(
         &'repo self,
-        prefix: &str,
-    ) -> Result<Vec<(semver::Version, Self::CommitTrait)>, ConvcoError>;
+        format: &TagFormat,
+    ) -> Result<Vec<(semver::Version, Self::CommitTrait)>, ConvcoError> {
+        Ok(self.version_tags(format)?.versions)
+    }
 
     fn revparse_single(&'repo self, spec: &str) -> Result<Self::CommitTrait, ConvcoError>;
 
+    /// Commits pointed to by the remote-tracking branches (`refs/remotes/*`).
+    fn remote_tips(&'repo self) -> Result<Vec<Self::CommitTrait>, ConvcoError>;
+
     fn revision_time(
         &'repo self,
         spec: &str,
@@ -69,6 +143,188 @@ pub trait Repo<'repo>: Sized {
     ) -> Result<jiff::Zoned, ConvcoError>;
 
     fn url(&self, remote: &str) -> Result<Option<String>, ConvcoError>;
+
+    /// The root of the working tree, `None` for a bare repository.
+    fn workdir(&self) -> Option<PathBuf>;
+
+    /// The mailmap of `.mailmap`, `mailmap.file` and `mailmap.blob`.
+    fn mailmap(&'repo self) -> Result<<Self::CommitTrait as CommitTrait>::Mailmap, ConvcoError>;
+
+    /// Walks the history of `tips` once, with the packages that every commit changes.
+    /// The packages are given by their pathspecs, a package without paths has every commit.
+    fn history(
+        &'repo self,
+        tips: &[Self::CommitTrait],
+        packages: &[Vec<String>],
+    ) -> Result<History<Self::CommitTrait>, ConvcoError>;
+}
+
+/// Where the commits of a walk come from: a [`Repo`], or a package of a [`History`]
+/// that is walked once for all the packages.
+pub trait Walk<'a, C> {
+    /// See [`Repo::find_last_version`].
+    fn last_version(
+        &'a self,
+        commit: &C,
+        ignore_prereleases: bool,
+        semvers: &[(semver::Version, C)],
+    ) -> Result<Option<(semver::Version, C)>, ConvcoError>;
+
+    /// See [`Repo::revwalk`].
+    fn walk(&'a self, options: RevWalkOptions<'a, C>) -> Result<RevWalkIter<'a, C>, ConvcoError>;
+}
+
+impl<'repo, R: Repo<'repo>> Walk<'repo, R::CommitTrait> for R {
+    fn last_version(
+        &'repo self,
+        commit: &R::CommitTrait,
+        ignore_prereleases: bool,
+        semvers: &[(semver::Version, R::CommitTrait)],
+    ) -> Result<Option<(semver::Version, R::CommitTrait)>, ConvcoError> {
+        self.find_last_version(commit, ignore_prereleases, semvers)
+    }
+
+    fn walk(
+        &'repo self,
+        options: RevWalkOptions<'repo, R::CommitTrait>,
+    ) -> Result<RevWalkIter<'repo, R::CommitTrait>, ConvcoError> {
+        self.revwalk(options)
+    }
+}
+
+/// A commit of a [`History`].
+#[derive(Debug, Clone)]
+pub struct HistoryCommit<C> {
+    pub commit: C,
+    /// The ids of the parents, the first parent first.
+    pub parents: Vec<String>,
+    /// The indices of the packages the commit changes.
+    pub packages: Vec<usize>,
+}
+
+/// The history of a monorepo, walked once for all its packages, see [`Repo::history`].
+/// The mailmap is a parameter of its own, so that the history stays covariant in the commits.
+pub struct History<C: CommitTrait, M = <C as CommitTrait>::Mailmap> {
+    commits: Vec<HistoryCommit<C>>,
+    positions: HashMap<String, usize>,
+    /// The mailmap of the authors of the commit filters.
+    mailmap: M,
+}
+
+impl<C: CommitTrait<Mailmap = M>, M> History<C, M> {
+    /// The history of `commits`, in the order of the walk.
+    pub fn new(commits: Vec<HistoryCommit<C>>, mailmap: M) -> Self {
+        let positions = commits
+            .iter()
+            .enumerate()
+            .map(|(position, commit)| (commit.commit.id(), position))
+            .collect();
+        Self {
+            commits,
+            positions,
+            mailmap,
+        }
+    }
+
+    /// The commits of the package with index `package`.
+    pub fn package(&self, package: usize) -> PackageHistory<'_, C, M> {
+        PackageHistory {
+            history: self,
+            package,
+        }
+    }
+
+    /// The positions of the `tips` and their ancestors, only through the first parents with `first_parent`.
+    fn ancestors<'c>(
+        &self,
+        tips: impl IntoIterator<Item = &'c C>,
+        first_parent: bool,
+    ) -> HashSet<usize>
+    where
+        C: 'c,
+    {
+        let mut stack: Vec<usize> = tips
+            .into_iter()
+            .filter_map(|tip| self.positions.get(&tip.id()).copied())
+            .collect();
+        let mut ancestors = HashSet::new();
+        while let Some(position) = stack.pop() {
+            if !ancestors.insert(position) {
+                continue;
+            }
+            let parents = &self.commits[position].parents;
+            let parents = match first_parent {
+                true => &parents[..parents.len().min(1)],
+                false => &parents[..],
+            };
+            stack.extend(
+                parents
+                    .iter()
+                    .filter_map(|parent| self.positions.get(parent).copied()),
+            );
+        }
+        ancestors
+    }
+}
+
+/// The commits of one package of a [`History`].
+/// A walk with `paths` has the commits that change the package, a walk without has all commits.
+pub struct PackageHistory<'h, C: CommitTrait, M = <C as CommitTrait>::Mailmap> {
+    history: &'h History<C, M>,
+    package: usize,
+}
+
+impl<'a, C: CommitTrait<Mailmap = M> + 'a, M> Walk<'a, C> for PackageHistory<'_, C, M> {
+    fn last_version(
+        &'a self,
+        commit: &C,
+        ignore_prereleases: bool,
+        semvers: &[(semver::Version, C)],
+    ) -> Result<Option<(semver::Version, C)>, ConvcoError> {
+        let reachable = self.history.ancestors([commit], false);
+        Ok(semvers
+            .iter()
+            .filter(|(version, _)| !ignore_prereleases || version.pre.is_empty())
+            .find(|(_, commit)| {
+                self.history
+                    .positions
+                    .get(&commit.id())
+                    .is_some_and(|position| reachable.contains(position))
+            })
+            .map(|(version, commit)| (version.clone(), commit.clone())))
+    }
+
+    fn walk(&'a self, options: RevWalkOptions<'a, C>) -> Result<RevWalkIter<'a, C>, ConvcoError> {
+        let hidden = self.history.ancestors(&options.from_rev, false);
+        let reachable = self
+            .history
+            .ancestors([&options.to_rev], options.first_parent);
+        let mut commits = Vec::new();
+        for (position, commit) in self.history.commits.iter().enumerate() {
+            if !reachable.contains(&position)
+                || hidden.contains(&position)
+                || !options.paths.is_empty() && !commit.packages.contains(&self.package)
+                || options.no_merge_commits && commit.parents.len() > 1
+                || !options
+                    .filters
+                    .matches(&commit.commit, &self.history.mailmap)
+            {
+                continue;
+            }
+            let message = commit.commit.commit_message()?;
+            if options.no_revert_commits && Revert::parse(&message).is_some() {
+                continue;
+            }
+            commits.push(match options.parser.parse(&message) {
+                Ok(conventional_commit) => Ok(Commit {
+                    conventional_commit,
+                    commit: commit.commit.clone(),
+                }),
+                Err(e) => Err((e.into(), commit.commit.clone())),
+            });
+        }
+        Ok(Box::new(commits.into_iter()))
+    }
 }
 
 macro_rules! define_max_component_iter {
@@ -131,9 +387,81 @@ pub struct RevWalkOptions<'a, C> {
     pub first_parent: bool,
     /// Include or exclude merge commits (more than 1 parent)
     pub no_merge_commits: bool,
-    /// Ignore revert commits (Starting with Revert..)
+    /// Ignore revert commits, see [`Revert`](crate::Revert)
     pub no_revert_commits: bool,
     /// Paths to include, usefull for monorepos
     pub paths: Vec<String>,
+    /// Dates, authors and trailers of the commits to include
+    pub filters: CommitFilters,
     pub parser: &'a CommitParser,
 }
+
+/// Filters of the commits of a revwalk, by commit date, author and trailer.
+#[derive(Clone, Debug, Default)]
+pub struct CommitFilters {
+    /// Only commits committed at or after this time.
+    pub since: Option<jiff::Timestamp>,
+    /// Only commits committed at or before this time.
+    pub until: Option<jiff::Timestamp>,
+    /// Only commits whose author, `Name <email>` after the mailmap, matches one of the regexes.
+    pub authors: Vec<Regex>,
+    /// Leave out commits whose author, `Name <email>` after the mailmap, matches one of the regexes.
+    pub exclude_authors: Vec<Regex>,
+    /// Leave out commits with one of these trailers.
+    pub exclude_trailers: Vec<String>,
+    /// Only commits with one of these trailers.
+    pub include_trailers: Vec<String>,
+}
+
+impl CommitFilters {
+    /// `true` if the commit passes all the filters, with the author resolved through `mailmap`.
+    pub fn matches<C: CommitTrait>(&self, commit: &C, mailmap: &C::Mailmap) -> bool {
+        if self.since.is_some() || self.until.is_some() {
+            let Ok(time) = commit.commit_time() else {
+                return false;
+            };
+            let time = time.timestamp();
+            if self.since.is_some_and(|since| time < since)
+                || self.until.is_some_and(|until| time > until)
+            {
+                return false;
+            }
+        }
+        if !self.authors.is_empty() || !self.exclude_authors.is_empty() {
+            let Ok(Signature { name, email }) = commit.author_with_mailmap(mailmap) else {
+                return false;
+            };
+            let author = format!("{name} <{email}>");
+            let is_author = |regex: &Regex| regex.is_match(&author);
+            if self.exclude_authors.iter().any(is_author)
+                || !self.authors.is_empty() && !self.authors.iter().any(is_author)
+            {
+                return false;
+            }
+        }
+        if !self.exclude_trailers.is_empty() || !self.include_trailers.is_empty() {
+            let Ok(message) = commit.commit_message() else {
+                return false;
+            };
+            let has_trailer = |token: &String| has_trailer(&message, token);
+            if self.exclude_trailers.iter().any(has_trailer)
+                || !self.include_trailers.is_empty()
+                    && !self.include_trailers.iter().any(has_trailer)
+            {
+                return false;
+            }
+        }
+        true
+    }
+}
+
+/// `true` if the last paragraph of the message, after the subject, has a `token: value` trailer.
+fn has_trailer(message: &str, token: &str) -> bool {
+    let paragraphs = message.trim().split("\n\n").skip(1);
+    paragraphs.last().is_some_and(|trailers| {
+        trailers.lines().any(|line| {
+            line.split_once(':')
+                .is_some_and(|(key, _)| key.trim_end().eq_ignore_ascii_case(token))
+        })
+    })
+}
@@ -1,4 +1,8 @@
-use std::{borrow::Cow, collections::HashSet};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    path::{Path, PathBuf},
+};
 
 use bstr::ByteSlice;
 use git2::{Delta, Pathspec, PathspecFlags};
@@ -7,10 +11,13 @@ use jiff::{
     Timestamp,
 };
 
-use super::{Commit, CommitTrait, Repo, RevWalkIter, RevWalkOptions};
-use crate::error::ConvcoError;
+use super::{
+    Commit, CommitTrait, History, HistoryCommit, Repo, RevWalkIter, RevWalkOptions, Signature,
+};
+use crate::{conventional::Revert, error::ConvcoError};
 
 impl CommitTrait for git2::Commit<'_> {
+    type Mailmap = git2::Mailmap;
     type ObjectId = git2::Oid;
 
     fn short_id(&self) -> String {
@@ -43,6 +50,41 @@ impl CommitTrait for git2::Commit<'_> {
 
         Ok(timestamp.to_zoned(tz))
     }
+
+    fn author(&self) -> Result<Signature, ConvcoError> {
+        Ok(signature(&self.author()))
+    }
+
+    fn committer(&self) -> Result<Signature, ConvcoError> {
+        Ok(signature(&self.committer()))
+    }
+
+    fn author_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError> {
+        Ok(signature(&self.author_with_mailmap(mailmap)?))
+    }
+
+    fn committer_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError> {
+        Ok(signature(&self.committer_with_mailmap(mailmap)?))
+    }
+
+    fn signature_with_mailmap(
+        mailmap: &Self::Mailmap,
+        unmapped: Signature,
+    ) -> Result<Signature, ConvcoError> {
+        // git2 rejects an empty name or email, the mailmap has no entry for it either
+        let Ok(raw) = git2::Signature::new(&unmapped.name, &unmapped.email, &git2::Time::new(0, 0))
+        else {
+            return Ok(unmapped);
+        };
+        Ok(signature(&mailmap.resolve_signature(&raw)?))
+    }
+}
+
+fn signature(signature: &git2::Signature<'_>) -> Signature {
+    Signature {
+        name: signature.name_bytes().to_str_lossy().into_owned(),
+        email: signature.email_bytes().to_str_lossy().into_owned(),
+    }
 }
 
 impl<'repo> Repo<'repo> for git2::Repository {
@@ -52,6 +94,10 @@ impl<'repo> Repo<'repo> for git2::Repository {
         Ok(git2::Repository::open_from_env()?)
     }
 
+    fn workdir(&self) -> Option<PathBuf> {
+        git2::Repository::workdir(self).map(Path::to_owned)
+    }
+
     fn url(&self, remote: &str) -> Result<Option<String>, ConvcoError> {
         match self.find_remote(remote) {
             Ok(remote) => Ok(Some(remote.url()?.to_owned())),
@@ -60,6 +106,10 @@ impl<'repo> Repo<'repo> for git2::Repository {
         }
     }
 
+    fn mailmap(&'repo self) -> Result<git2::Mailmap, ConvcoError> {
+        Ok(git2::Repository::mailmap(self)?)
+    }
+
     fn find_last_version(
         &'repo self,
         commit: &Self::CommitTrait,
@@ -96,6 +146,9 @@ impl<'repo> Repo<'repo> for git2::Repository {
         if options.no_merge_commits {
             revwalk = Box::new(revwalk.filter(move |commit| commit.parent_count() <= 1));
         }
+        let filters = options.filters;
+        let mailmap = Repo::mailmap(self)?;
+        revwalk = Box::new(revwalk.filter(move |commit| filters.matches(commit, &mailmap)));
         if !options.paths.is_empty() {
             let pathspec_filter = Git2PathspecFilter::new(options.paths.as_slice());
             revwalk = Box::new(revwalk.filter(move |commit| {
@@ -107,7 +160,7 @@ impl<'repo> Repo<'repo> for git2::Repository {
         let revwalk: Box<dyn Iterator<Item = _>> = Box::new(revwalk.filter_map(move |commit| {
             let message = commit.message().ok().map(ToOwned::to_owned);
             message.and_then(|msg| {
-                if options.no_revert_commits && msg.starts_with("Revert \"") {
+                if options.no_revert_commits && Revert::parse(&msg).is_some() {
                     return None;
                 }
                 Some(match options.parser.parse(&msg) {
@@ -123,30 +176,66 @@ impl<'repo> Repo<'repo> for git2::Repository {
         Ok(revwalk)
     }
 
-    fn semver_tags(
+    fn history(
         &'repo self,
-        prefix: &str,
-    ) -> Result<Vec<(semver::Version, Self::CommitTrait)>, ConvcoError> {
-        let mut versions = self
+        tips: &[Self::CommitTrait],
+        packages: &[Vec<String>],
+    ) -> Result<History<Self::CommitTrait>, ConvcoError> {
+        let filters = packages
+            .iter()
+            .map(|paths| (!paths.is_empty()).then(|| Git2PathspecFilter::new(paths)))
+            .collect::<Vec<_>>();
+        let mut revwalk = self.revwalk()?;
+        for tip in tips {
+            revwalk.push(tip.id())?;
+        }
+        let mut commits = Vec::new();
+        for oid in revwalk {
+            let commit = self.find_commit(oid?)?;
+            let packages = filters
+                .iter()
+                .enumerate()
+                .filter(|(_, filter)| match filter {
+                    None => true,
+                    Some(filter) => filter
+                        .as_ref()
+                        .is_some_and(|filter| self.commit_changes_path(&commit, filter)),
+                })
+                .map(|(package, _)| package)
+                .collect();
+            let parents = commit.parent_ids().map(|id| id.to_string()).collect();
+            commits.push(HistoryCommit {
+                commit,
+                parents,
+                packages,
+            });
+        }
+        Ok(History::new(commits, Repo::mailmap(self)?))
+    }
+
+    fn tags(&'repo self, prefix: &str) -> Result<Vec<(String, Self::CommitTrait)>, ConvcoError> {
+        Ok(self
             .references_glob(&format!("refs/tags/{prefix}*"))?
             .flatten()
             .filter_map(|tag| {
-                let name = tag.shorthand_bytes();
-                let name = name.strip_prefix(prefix.as_bytes()).unwrap();
-                name.to_str()
-                    .ok()
-                    .and_then(|name| semver::Version::parse(name).ok())
-                    .and_then(|version| tag.peel_to_commit().ok().map(|commit| (version, commit)))
+                let name = tag.shorthand().ok()?.to_owned();
+                tag.peel_to_commit().ok().map(|commit| (name, commit))
             })
-            .collect::<Vec<_>>();
-        versions.sort_by(|a, b| b.0.cmp(&a.0));
-        Ok(versions)
+            .collect())
     }
 
     fn revparse_single(&'repo self, spec: &str) -> Result<Self::CommitTrait, ConvcoError> {
         Ok(self.revparse_single(spec)?.peel_to_commit()?)
     }
 
+    fn remote_tips(&'repo self) -> Result<Vec<Self::CommitTrait>, ConvcoError> {
+        Ok(self
+            .references_glob("refs/remotes/*")?
+            .flatten()
+            .filter_map(|reference| reference.peel_to_commit().ok())
+            .collect())
+    }
+
     fn revision_time(
         &'repo self,
         spec: &str,
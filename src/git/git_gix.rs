@@ -1,4 +1,9 @@
-use std::{borrow::Cow, collections::HashSet, convert::Infallible};
+use std::{
+    borrow::Cow,
+    collections::HashSet,
+    convert::Infallible,
+    path::{Path, PathBuf},
+};
 
 use bstr::ByteSlice;
 use gix::{
@@ -10,10 +15,13 @@ use jiff::{
     Timestamp,
 };
 
-use super::{Commit, CommitTrait, Repo, RevWalkIter, RevWalkOptions};
-use crate::error::ConvcoError;
+use super::{
+    Commit, CommitTrait, History, HistoryCommit, Repo, RevWalkIter, RevWalkOptions, Signature,
+};
+use crate::{conventional::Revert, error::ConvcoError};
 
 impl CommitTrait for gix::Commit<'_> {
+    type Mailmap = gix::mailmap::Snapshot;
     type ObjectId = gix::ObjectId;
 
     fn short_id(&self) -> String {
@@ -41,6 +49,58 @@ impl CommitTrait for gix::Commit<'_> {
 
         Ok(timestamp.to_zoned(tz))
     }
+
+    fn author(&self) -> Result<Signature, ConvcoError> {
+        Ok(signature(self.author()?))
+    }
+
+    fn committer(&self) -> Result<Signature, ConvcoError> {
+        Ok(signature(self.committer()?))
+    }
+
+    fn author_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError> {
+        Ok(signature(
+            mailmap
+                .resolve(self.author()?)
+                .to_ref(&mut Default::default()),
+        ))
+    }
+
+    fn committer_with_mailmap(&self, mailmap: &Self::Mailmap) -> Result<Signature, ConvcoError> {
+        Ok(signature(
+            mailmap
+                .resolve(self.committer()?)
+                .to_ref(&mut Default::default()),
+        ))
+    }
+
+    fn signature_with_mailmap(
+        mailmap: &Self::Mailmap,
+        unmapped: Signature,
+    ) -> Result<Signature, ConvcoError> {
+        let Some(resolved) = mailmap.try_resolve_ref(gix::actor::SignatureRef {
+            name: unmapped.name.as_bytes().as_bstr(),
+            email: unmapped.email.as_bytes().as_bstr(),
+            time: "",
+        }) else {
+            return Ok(unmapped);
+        };
+        Ok(Signature {
+            name: resolved.name.map_or(unmapped.name.clone(), |name| {
+                name.to_str_lossy().into_owned()
+            }),
+            email: resolved.email.map_or(unmapped.email.clone(), |email| {
+                email.to_str_lossy().into_owned()
+            }),
+        })
+    }
+}
+
+fn signature(signature: gix::actor::SignatureRef<'_>) -> Signature {
+    Signature {
+        name: signature.name.to_str_lossy().into_owned(),
+        email: signature.email.to_str_lossy().into_owned(),
+    }
 }
 
 impl<'repo> Repo<'repo> for gix::Repository {
@@ -50,6 +110,10 @@ impl<'repo> Repo<'repo> for gix::Repository {
         Ok(gix::discover(".")?)
     }
 
+    fn workdir(&self) -> Option<PathBuf> {
+        gix::Repository::workdir(self).map(Path::to_owned)
+    }
+
     fn url(&self, remote: &str) -> Result<Option<String>, ConvcoError> {
         match self.find_remote(remote) {
             Ok(remote) => Ok(remote
@@ -60,6 +124,12 @@ impl<'repo> Repo<'repo> for gix::Repository {
         }
     }
 
+    fn mailmap(&'repo self) -> Result<gix::mailmap::Snapshot, ConvcoError> {
+        let mut mailmap = gix::mailmap::Snapshot::default();
+        self.open_mailmap_into(&mut mailmap)?;
+        Ok(mailmap)
+    }
+
     fn find_last_version(
         &'repo self,
         commit: &Self::CommitTrait,
@@ -109,16 +179,11 @@ impl<'repo> Repo<'repo> for gix::Repository {
         if options.no_merge_commits {
             revwalk = Box::new(revwalk.filter(move |(info, _)| info.parent_ids.len() <= 1));
         }
+        let filters = options.filters;
+        let mailmap = Repo::mailmap(self)?;
+        revwalk = Box::new(revwalk.filter(move |(_, commit)| filters.matches(commit, &mailmap)));
         if !paths.is_empty() {
-            let mut pathspec = self
-                .pathspec(
-                    true,
-                    paths.into_iter().map(bstr::BString::from),
-                    true,
-                    &self.index().unwrap(),
-                    Source::IdMapping,
-                )
-                .unwrap();
+            let mut pathspec = self.pathspec_of(&paths);
             revwalk = Box::new(revwalk.filter(move |(info, commit)| {
                 self.commit_changes_path(commit, &info.parent_ids, &mut pathspec)
             }));
@@ -127,7 +192,7 @@ impl<'repo> Repo<'repo> for gix::Repository {
             Box::new(revwalk.filter_map(move |(_, commit)| {
                 let msg = commit.message_raw().ok()?.to_str().ok()?;
 
-                if msg.starts_with("Revert \"") {
+                if Revert::parse(msg).is_some() {
                     return None;
                 }
 
@@ -156,39 +221,89 @@ impl<'repo> Repo<'repo> for gix::Repository {
         Ok(revwalk)
     }
 
-    fn semver_tags(
+    fn history(
         &'repo self,
-        prefix: &str,
-    ) -> Result<Vec<(semver::Version, Self::CommitTrait)>, ConvcoError> {
-        let mut versions = self
+        tips: &[Self::CommitTrait],
+        packages: &[Vec<String>],
+    ) -> Result<History<Self::CommitTrait>, ConvcoError> {
+        let mut pathspecs = packages
+            .iter()
+            .map(|paths| (!paths.is_empty()).then(|| self.pathspec_of(paths)))
+            .collect::<Vec<_>>();
+        let mut commits = Vec::new();
+        for info in self
+            .rev_walk(tips.iter().map(|tip| tip.id))
+            .sorting(gix::revision::walk::Sorting::BreadthFirst)
+            .all()?
+            .flatten()
+        {
+            let Ok(commit) = info.object() else {
+                continue;
+            };
+            let packages = pathspecs
+                .iter_mut()
+                .enumerate()
+                .filter_map(|(package, pathspec)| match pathspec {
+                    None => Some(package),
+                    Some(pathspec) => self
+                        .commit_changes_path(&commit, &info.parent_ids, pathspec)
+                        .then_some(package),
+                })
+                .collect();
+            let parents = info.parent_ids.iter().map(ToString::to_string).collect();
+            commits.push(HistoryCommit {
+                commit,
+                parents,
+                packages,
+            });
+        }
+        Ok(History::new(commits, Repo::mailmap(self)?))
+    }
+
+    fn tags(&'repo self, prefix: &str) -> Result<Vec<(String, Self::CommitTrait)>, ConvcoError> {
+        Ok(self
             .references()?
             .tags()?
             .flatten()
             .filter_map(|mut tag| {
-                let name = tag.name().as_bstr().strip_prefix(b"refs/tags/").unwrap();
-                if name.starts_with(prefix.as_bytes()) {
-                    let name = name.strip_prefix(prefix.as_bytes()).unwrap();
-                    let version = semver::Version::parse(name.to_str().ok()?).ok()?;
-                    let commit = tag
-                        .peel_to_commit()
-                        .ok()?
-                        .detach()
-                        .attach(self)
-                        .into_commit();
-                    Some((version, commit))
-                } else {
-                    None
+                let name = tag.name().as_bstr().strip_prefix(b"refs/tags/")?;
+                if !name.starts_with(prefix.as_bytes()) {
+                    return None;
                 }
+                let name = name.to_str().ok()?.to_owned();
+                let commit = tag
+                    .peel_to_commit()
+                    .ok()?
+                    .detach()
+                    .attach(self)
+                    .into_commit();
+                Some((name, commit))
             })
-            .collect::<Vec<_>>();
-        versions.sort_by(|a, b| b.0.cmp(&a.0));
-        Ok(versions)
+            .collect())
     }
 
     fn revparse_single(&'repo self, spec: &str) -> Result<Self::CommitTrait, ConvcoError> {
         Ok(self.rev_parse_single(spec)?.object()?.peel_to_commit()?)
     }
 
+    fn remote_tips(&'repo self) -> Result<Vec<Self::CommitTrait>, ConvcoError> {
+        Ok(self
+            .references()?
+            .remote_branches()?
+            .flatten()
+            .filter_map(|mut reference| {
+                Some(
+                    reference
+                        .peel_to_commit()
+                        .ok()?
+                        .detach()
+                        .attach(self)
+                        .into_commit(),
+                )
+            })
+            .collect())
+    }
+
     fn revision_time(
         &'repo self,
         spec: &str,
@@ -221,6 +336,8 @@ fn zoned_from_git_time(seconds: i64, offset_seconds: i32) -> Result<jiff::Zoned,
 }
 
 trait GixExt {
+    fn pathspec_of(&self, paths: &[String]) -> Pathspec<'_>;
+
     fn commit_changes_path(
         &self,
         commit: &gix::Commit,
@@ -230,6 +347,19 @@ trait GixExt {
 }
 
 impl GixExt for gix::Repository {
+    fn pathspec_of(&self, paths: &[String]) -> Pathspec<'_> {
+        self.pathspec(
+            true,
+            paths
+                .iter()
+                .map(|path| bstr::BString::from(top_pathspec(path))),
+            true,
+            &self.index().unwrap(),
+            Source::IdMapping,
+        )
+        .unwrap()
+    }
+
     fn commit_changes_path(
         &self,
         commit: &gix::Commit,
@@ -282,3 +412,14 @@ impl GixExt for gix::Repository {
         }
     }
 }
+
+/// `pathspec` relative to the root of the repository instead of the current directory, as in git2.
+fn top_pathspec(pathspec: &str) -> String {
+    if let Some(pathspec) = pathspec.strip_prefix(":(") {
+        format!(":(top,{pathspec}")
+    } else if let Some(pathspec) = pathspec.strip_prefix(':') {
+        format!(":/{pathspec}")
+    } else {
+        format!(":(top){pathspec}")
+    }
+}
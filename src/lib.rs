@@ -5,12 +5,20 @@ pub mod strip;
 
 pub use conventional::{
     changelog,
-    commit::{Footer, FooterKey},
-    config::{commit_scope_eq, commit_type_eq, Increment, Type},
-    CommitParser, Config, ParseError,
+    commit::{ConventionalCommit, Footer, FooterKey},
+    config::{
+        commit_scope_eq, commit_type_eq, BumpFile, BumpFileType, BumpRule, ChangelogFooters,
+        GroupBy, Increment, Package, Platform, Scope, Type,
+    },
+    lint::{LintRules, Problem, RuleLevel},
+    version_scheme::{
+        CalverToken, Comparator, TagFormat, TagMismatch, VersionFormat, VersionScheme,
+    },
+    CommitParser, Config, ParseError, Revert, SquashHandling,
 };
 pub use error::ConvcoError;
 pub use git::{
-    open_repo, Commit, CommitTrait, MaxMajorsIterExt, MaxMinorsIterExt, MaxPatchesIterExt, Repo,
-    RevWalkOptions,
+    open_repo, Commit, CommitFilters, CommitTrait, History, HistoryCommit, MaxMajorsIterExt,
+    MaxMinorsIterExt, MaxPatchesIterExt, PackageHistory, Repo, RevWalkIter, RevWalkOptions,
+    Signature, VersionTags, Walk,
 };
@@ -49,6 +49,11 @@ fn main() -> anyhow::Result<()> {
             let repo = open_repo()?;
             command.exec(Config::from_repo(&repo, config_path)?)
         }
+        cli::Command::Release(command) => {
+            let repo = open_repo()?;
+            command.exec(Config::from_repo(&repo, config_path)?)
+        }
+        cli::Command::Hooks(command) => command.exec(Config::default()),
         #[cfg(feature = "completions")]
         cli::Command::Completions(command) => command.exec(Config::default()),
     };
@@ -8,7 +8,11 @@ use std::{
     sync::{Mutex, OnceLock},
 };
 
-use convco::{open_repo, CommitParser, CommitTrait, Repo, RevWalkOptions};
+use convco::{
+    open_repo, CommitFilters, CommitParser, CommitTrait, Config, Repo, RevWalkOptions, Signature,
+    TagFormat, TagMismatch, VersionScheme, Walk,
+};
+use regex::Regex;
 use tempfile::{tempdir, TempDir};
 
 fn cwd_lock() -> &'static Mutex<()> {
@@ -54,6 +58,10 @@ fn setup_sha256_repo() -> TempDir {
     temp
 }
 
+fn semver_tags(prefix: &str) -> TagFormat {
+    TagFormat::new(prefix, &Config::default()).unwrap()
+}
+
 fn with_repo<T>(repo: &Path, test: impl FnOnce() -> T) -> T {
     let _guard = cwd_lock().lock().unwrap_or_else(|err| err.into_inner());
     let original_dir = env::current_dir().unwrap();
@@ -106,7 +114,7 @@ fn sha256_repositories_support_core_backend_operations() {
             "feat(lib): lib change\n\nbody line\n"
         );
 
-        let semvers = Repo::semver_tags(&repo, "v").unwrap();
+        let semvers = Repo::semver_tags(&repo, &semver_tags("v")).unwrap();
         assert_eq!(semvers.len(), 1);
         assert_eq!(semvers[0].0.to_string(), "1.0.0");
 
@@ -126,6 +134,7 @@ fn sha256_repositories_support_core_backend_operations() {
                 no_merge_commits: false,
                 no_revert_commits: false,
                 paths: vec!["packages/app".to_owned()],
+                filters: CommitFilters::default(),
                 parser: &parser,
             },
         )
@@ -217,6 +226,121 @@ fn commit_message_returns_full_multiline_message() {
     });
 }
 
+#[test]
+fn author_and_committer_are_read_from_the_commit() {
+    let temp = setup_repo();
+
+    let output = Command::new("git")
+        .args(["commit", "--allow-empty", "-m", "feat: subject"])
+        .args(["--author", "Jane Doe <jane@example.com>"])
+        .current_dir(temp.path())
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+
+    with_repo(temp.path(), || {
+        let repo = open_repo().unwrap();
+        let commit = Repo::revparse_single(&repo, "HEAD").unwrap();
+        assert_eq!(
+            CommitTrait::author(&commit).unwrap(),
+            Signature {
+                name: "Jane Doe".into(),
+                email: "jane@example.com".into()
+            }
+        );
+        assert_eq!(
+            CommitTrait::committer(&commit).unwrap(),
+            Signature {
+                name: "Convco Test".into(),
+                email: "test@example.com".into()
+            }
+        );
+    });
+}
+
+#[test]
+fn author_and_committer_are_resolved_through_the_mailmap() {
+    let temp = setup_repo();
+    let repo = temp.path();
+
+    fs::write(
+        repo.join(".mailmap"),
+        "Jane Doe <jane@example.com> <jane@old.example.com>\n",
+    )
+    .unwrap();
+    fs::write(
+        repo.join("extra.mailmap"),
+        "Convco <convco@example.com> Convco Test <test@example.com>\n",
+    )
+    .unwrap();
+    git(repo, &["config", "mailmap.file", "extra.mailmap"]);
+    git(repo, &["add", ".mailmap"]);
+    git(
+        repo,
+        &[
+            "commit",
+            "-m",
+            "feat: subject",
+            "--author",
+            "jane <jane@old.example.com>",
+        ],
+    );
+
+    with_repo(repo, || {
+        let repo = open_repo().unwrap();
+        let mailmap = Repo::mailmap(&repo).unwrap();
+        let commit = Repo::revparse_single(&repo, "HEAD").unwrap();
+        assert_eq!(
+            CommitTrait::author_with_mailmap(&commit, &mailmap).unwrap(),
+            Signature {
+                name: "Jane Doe".into(),
+                email: "jane@example.com".into()
+            }
+        );
+        assert_eq!(
+            CommitTrait::committer_with_mailmap(&commit, &mailmap).unwrap(),
+            Signature {
+                name: "Convco".into(),
+                email: "convco@example.com".into()
+            }
+        );
+        assert_eq!(
+            CommitTrait::author(&commit).unwrap(),
+            Signature {
+                name: "jane".into(),
+                email: "jane@old.example.com".into()
+            }
+        );
+
+        // e.g. the co-authors of `Co-authored-by` trailers
+        fn with_mailmap<C: CommitTrait>(_: &C, mailmap: &C::Mailmap, name: &str) -> Signature {
+            let email = format!("{}@old.example.com", name.to_lowercase());
+            C::signature_with_mailmap(
+                mailmap,
+                Signature {
+                    name: name.into(),
+                    email,
+                },
+            )
+            .unwrap()
+        }
+        assert_eq!(
+            with_mailmap(&commit, &mailmap, "jane"),
+            Signature {
+                name: "Jane Doe".into(),
+                email: "jane@example.com".into()
+            }
+        );
+        assert_eq!(
+            with_mailmap(&commit, &mailmap, "John"),
+            Signature {
+                name: "John".into(),
+                email: "john@old.example.com".into()
+            }
+        );
+    });
+}
+
 #[test]
 fn semver_tags_are_sorted_and_resolved_to_commits() {
     let temp = setup_repo();
@@ -229,7 +353,7 @@ fn semver_tags_are_sorted_and_resolved_to_commits() {
 
     with_repo(temp.path(), || {
         let repo = open_repo().unwrap();
-        let versions = Repo::semver_tags(&repo, "v").unwrap();
+        let versions = Repo::semver_tags(&repo, &semver_tags("v")).unwrap();
         let version_numbers = versions
             .iter()
             .map(|(version, _)| version.to_string())
@@ -243,6 +367,120 @@ fn semver_tags_are_sorted_and_resolved_to_commits() {
     });
 }
 
+#[test]
+fn calver_tags_are_sorted_and_find_the_last_version() {
+    let temp = setup_repo();
+
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: one"]);
+    git(temp.path(), &["tag", "2026.9"]);
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: two"]);
+    git(temp.path(), &["tag", "2026.42"]);
+    git(temp.path(), &["tag", "1.2.3"]);
+    git(
+        temp.path(),
+        &["commit", "--allow-empty", "-m", "feat: three"],
+    );
+
+    with_repo(temp.path(), || {
+        let repo = open_repo().unwrap();
+        let config = Config {
+            version_scheme: VersionScheme::Calver {
+                format: "YYYY.WW".into(),
+            },
+            ..Config::default()
+        };
+        let format = TagFormat::new("", &config).unwrap();
+        let versions = Repo::semver_tags(&repo, &format).unwrap();
+        let tags = versions
+            .iter()
+            .map(|(version, _)| format.tag(version))
+            .collect::<Vec<_>>();
+        assert_eq!(tags, ["2026.42", "2026.9"]);
+
+        let head = Repo::revparse_single(&repo, "HEAD").unwrap();
+        let (version, commit) = Repo::find_last_version(&repo, &head, false, &versions)
+            .unwrap()
+            .unwrap();
+        assert_eq!(format.tag(&version), "2026.42");
+        assert_eq!(commit.commit_message().unwrap().as_ref(), "feat: two\n");
+    });
+}
+
+#[test]
+fn tag_pattern_reads_the_version_group_and_reports_other_tags() {
+    let temp = setup_repo();
+
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: one"]);
+    git(temp.path(), &["tag", "release-1.0.0-final"]);
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: two"]);
+    git(temp.path(), &["tag", "release-1.1.0-final"]);
+    git(temp.path(), &["tag", "release-1.2.0"]);
+    git(temp.path(), &["tag", "release-next-final"]);
+    git(temp.path(), &["tag", "service-a/v3.0.0"]);
+
+    with_repo(temp.path(), || {
+        let repo = open_repo().unwrap();
+        let config = Config {
+            tag_pattern: Some("^(?<version>.+)-final$".into()),
+            tag_template: Some("{{version}}-final".into()),
+            ..Config::default()
+        };
+        let format = TagFormat::new("release-", &config).unwrap();
+        let tags = Repo::version_tags(&repo, &format).unwrap();
+        let versions = tags
+            .versions
+            .iter()
+            .map(|(version, _)| format.tag(version))
+            .collect::<Vec<_>>();
+        assert_eq!(versions, ["release-1.1.0-final", "release-1.0.0-final"]);
+
+        let mut ignored = tags.ignored;
+        ignored.sort_by(|a, b| a.0.cmp(&b.0));
+        assert_eq!(
+            ignored,
+            [
+                ("release-1.2.0".to_owned(), TagMismatch::Pattern),
+                (
+                    "release-next-final".to_owned(),
+                    TagMismatch::Version("next".into())
+                ),
+            ]
+        );
+
+        let versions = Repo::semver_tags(&repo, &semver_tags("service-a/v")).unwrap();
+        assert_eq!(versions[0].0.to_string(), "3.0.0");
+    });
+}
+
+#[test]
+fn remote_tips_resolve_remote_tracking_branches() {
+    let temp = setup_repo();
+
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: one"]);
+    git(
+        temp.path(),
+        &["update-ref", "refs/remotes/origin/main", "HEAD"],
+    );
+    git(temp.path(), &["commit", "--allow-empty", "-m", "feat: two"]);
+    git(
+        temp.path(),
+        &["update-ref", "refs/remotes/fork/topic", "HEAD"],
+    );
+    git(temp.path(), &["tag", "v1.0.0"]);
+
+    with_repo(temp.path(), || {
+        let repo = open_repo().unwrap();
+        let mut messages = Repo::remote_tips(&repo)
+            .unwrap()
+            .iter()
+            .map(|commit| commit.commit_message().unwrap().into_owned())
+            .collect::<Vec<_>>();
+        messages.sort();
+
+        assert_eq!(messages, ["feat: one\n", "feat: two\n"]);
+    });
+}
+
 #[test]
 fn semver_tags_ignore_tags_that_do_not_resolve_to_commits() {
     let temp = setup_repo();
@@ -269,7 +507,7 @@ fn semver_tags_ignore_tags_that_do_not_resolve_to_commits() {
 
     with_repo(repo, || {
         let repo = open_repo().unwrap();
-        let versions = Repo::semver_tags(&repo, "v").unwrap();
+        let versions = Repo::semver_tags(&repo, &semver_tags("v")).unwrap();
         let version_numbers = versions
             .iter()
             .map(|(version, _)| version.to_string())
@@ -307,7 +545,7 @@ fn find_last_version_selects_highest_reachable_semver_in_non_linear_history() {
     with_repo(repo, || {
         let repo = open_repo().unwrap();
         let head = Repo::revparse_single(&repo, "HEAD").unwrap();
-        let semvers = Repo::semver_tags(&repo, "v").unwrap();
+        let semvers = Repo::semver_tags(&repo, &semver_tags("v")).unwrap();
         let version = Repo::find_last_version(&repo, &head, false, &semvers).unwrap();
 
         assert_eq!(
@@ -350,6 +588,7 @@ fn revparse_single_resolves_head_and_revwalk_filters_paths() {
                 no_merge_commits: false,
                 no_revert_commits: false,
                 paths: vec!["packages/app".to_owned()],
+                filters: CommitFilters::default(),
                 parser: &parser,
             },
         )
@@ -411,6 +650,7 @@ fn revwalk_messages(repo: &Path, paths: Vec<String>) -> Vec<String> {
                 no_merge_commits: false,
                 no_revert_commits: false,
                 paths,
+                filters: CommitFilters::default(),
                 parser: &parser,
             },
         )
@@ -475,6 +715,66 @@ fn revwalk_supports_include_and_exclude_pathspecs() {
     );
 }
 
+#[test]
+fn history_buckets_the_commits_of_every_package_from_the_root() {
+    let temp = setup_repo_with_pathspec_commits();
+    let packages = vec![
+        vec!["src".to_owned(), ":(exclude)src/generated".to_owned()],
+        vec![":!charts".to_owned()],
+        vec!["charts".to_owned()],
+        vec![],
+    ];
+    let expected = packages
+        .iter()
+        .map(|paths| revwalk_messages(temp.path(), paths.clone()))
+        .collect::<Vec<_>>();
+
+    // the paths are relative to the root of the repository, also from a subdirectory
+    with_repo(&temp.path().join("src"), || {
+        let repo = open_repo().unwrap();
+        let head = Repo::revparse_single(&repo, "HEAD").unwrap();
+        let history = repo
+            .history(std::slice::from_ref(&head), &packages)
+            .unwrap();
+        let parser = CommitParser::builder().build();
+        for (i, paths) in packages.iter().enumerate() {
+            let package = history.package(i);
+            let messages = Walk::walk(
+                &package,
+                RevWalkOptions {
+                    from_rev: vec![],
+                    to_rev: head.clone(),
+                    first_parent: false,
+                    no_merge_commits: false,
+                    no_revert_commits: false,
+                    paths: paths.clone(),
+                    filters: CommitFilters::default(),
+                    parser: &parser,
+                },
+            )
+            .unwrap()
+            .map(|commit| {
+                commit
+                    .unwrap()
+                    .commit
+                    .commit_message()
+                    .unwrap()
+                    .into_owned()
+            })
+            .collect::<Vec<_>>();
+            assert_eq!(messages, expected[i], "package {i}");
+        }
+
+        let semvers = repo.version_tags(&semver_tags("v")).unwrap().versions;
+        let (version, _) = history
+            .package(2)
+            .last_version(&head, false, &semvers)
+            .unwrap()
+            .unwrap();
+        assert_eq!(version, semver::Version::new(1, 0, 0));
+    });
+}
+
 #[test]
 fn revwalk_without_paths_works_in_bare_repository() {
     let temp = setup_repo();
@@ -514,6 +814,7 @@ fn revwalk_without_paths_works_in_bare_repository() {
                 no_merge_commits: false,
                 no_revert_commits: false,
                 paths: vec![],
+                filters: CommitFilters::default(),
                 parser: &parser,
             },
         )
@@ -524,3 +825,116 @@ fn revwalk_without_paths_works_in_bare_repository() {
         assert_eq!(commits.len(), 1);
     });
 }
+
+#[test]
+fn commit_filters_select_dates_authors_and_trailers() {
+    let temp = setup_repo();
+    let repo = temp.path();
+    let commit = |date: &str, author: &str, message: &str| {
+        let output = Command::new("git")
+            .args(["commit", "--allow-empty", "--author", author, "-m", message])
+            .env("GIT_AUTHOR_DATE", date)
+            .env("GIT_COMMITTER_DATE", date)
+            .current_dir(repo)
+            .output()
+            .unwrap();
+        assert!(output.status.success(), "{output:?}");
+    };
+    // the author filters see the canonical name of the mailmap
+    fs::write(
+        repo.join(".mailmap"),
+        "Convco Maintainer <test@example.com>\n",
+    )
+    .unwrap();
+    git(repo, &["add", ".mailmap"]);
+    commit(
+        "2024-01-01T12:00:00Z",
+        "Convco Test <test@example.com>",
+        "feat: january",
+    );
+    commit(
+        "2024-02-01T12:00:00Z",
+        "dependabot[bot] <bot@example.com>",
+        "fix: bump dependency",
+    );
+    commit(
+        "2024-03-01T12:00:00Z",
+        "Convco Test <test@example.com>",
+        "fix: march\n\nbody\n\nSkip-Changelog: true",
+    );
+
+    with_repo(repo, || {
+        let repo = open_repo().unwrap();
+        let head = Repo::revparse_single(&repo, "HEAD").unwrap();
+        let parser = CommitParser::builder().build();
+        let descriptions = |filters: CommitFilters| {
+            Repo::revwalk(
+                &repo,
+                RevWalkOptions {
+                    from_rev: vec![],
+                    to_rev: head.clone(),
+                    first_parent: false,
+                    no_merge_commits: false,
+                    no_revert_commits: false,
+                    paths: vec![],
+                    filters,
+                    parser: &parser,
+                },
+            )
+            .unwrap()
+            .map(|commit| commit.unwrap().conventional_commit.description)
+            .collect::<Vec<_>>()
+        };
+
+        assert_eq!(
+            descriptions(CommitFilters {
+                since: Some("2024-01-15T00:00:00Z".parse().unwrap()),
+                until: Some("2024-02-01T12:00:00Z".parse().unwrap()),
+                ..CommitFilters::default()
+            }),
+            ["bump dependency"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                authors: vec![Regex::new("^Convco").unwrap()],
+                ..CommitFilters::default()
+            }),
+            ["march", "january"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                exclude_authors: vec![Regex::new(r"\[bot\]").unwrap()],
+                ..CommitFilters::default()
+            }),
+            ["march", "january"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                authors: vec![Regex::new("^Convco Maintainer <test@").unwrap()],
+                ..CommitFilters::default()
+            }),
+            ["march", "january"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                exclude_authors: vec![Regex::new("Maintainer").unwrap()],
+                ..CommitFilters::default()
+            }),
+            ["bump dependency"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                exclude_trailers: vec!["skip-changelog".to_owned()],
+                ..CommitFilters::default()
+            }),
+            ["bump dependency", "january"]
+        );
+        assert_eq!(
+            descriptions(CommitFilters {
+                include_trailers: vec!["Skip-Changelog".to_owned()],
+                ..CommitFilters::default()
+            }),
+            ["march"]
+        );
+    });
+}
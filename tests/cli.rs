@@ -119,6 +119,41 @@ fn setup_repo_with_revert_commit() -> Result<TempDir, Box<dyn std::error::Error>
     Ok(temp)
 }
 
+/// Two packages, `app` tagged `app-v1.0.0` and `lib` tagged `lib@0.1.0`, each with one change since.
+fn setup_monorepo() -> Result<TempDir, Box<dyn std::error::Error>> {
+    let temp = tempdir()?;
+    let repo = temp.path();
+
+    git(repo, &["init"])?;
+    git(repo, &["config", "user.name", "Convco Test"])?;
+    git(repo, &["config", "user.email", "test@example.com"])?;
+
+    fs::create_dir_all(repo.join("packages/app"))?;
+    fs::create_dir_all(repo.join("packages/lib"))?;
+    fs::write(repo.join("packages/app/app.txt"), "app")?;
+    git(repo, &["add", "packages/app/app.txt"])?;
+    git(repo, &["commit", "-m", "feat(app): first app feature"])?;
+    git(repo, &["tag", "app-v1.0.0"])?;
+    fs::write(repo.join("packages/lib/lib.txt"), "lib")?;
+    git(repo, &["add", "packages/lib/lib.txt"])?;
+    git(repo, &["commit", "-m", "feat(lib): first lib feature"])?;
+    git(repo, &["tag", "lib@0.1.0"])?;
+
+    fs::write(repo.join("packages/app/app.txt"), "app fix")?;
+    git(repo, &["add", "packages/app/app.txt"])?;
+    git(repo, &["commit", "-m", "fix(app): app fix"])?;
+    fs::write(repo.join("packages/lib/lib.txt"), "lib feature")?;
+    git(repo, &["add", "packages/lib/lib.txt"])?;
+    git(repo, &["commit", "-m", "feat(lib): lib feature"])?;
+
+    fs::write(
+        repo.join(".convco"),
+        "packages:\n  - name: app\n    paths: [packages/app]\n  - name: lib\n    paths: [packages/lib]\n    tagPrefix: lib@\n    changelog: LIB_CHANGELOG.md\n",
+    )?;
+
+    Ok(temp)
+}
+
 fn mask_oids(input: &str) -> String {
     let mut result = String::with_capacity(input.len());
     let chars: Vec<char> = input.chars().collect();
@@ -149,8 +184,8 @@ fn mask_oids(input: &str) -> String {
 fn mask_dates(input: &str) -> String {
     static DATE_REGEX: OnceLock<Regex> = OnceLock::new();
     let date_regex =
-        DATE_REGEX.get_or_init(|| Regex::new(r"\([0-9]{4}-[0-9]{2}-[0-9]{2}\)").unwrap());
-    date_regex.replace_all(input, "(<DATE>)").to_string()
+        DATE_REGEX.get_or_init(|| Regex::new(r"\b[0-9]{4}-[0-9]{2}-[0-9]{2}\b").unwrap());
+    date_regex.replace_all(input, "<DATE>").to_string()
 }
 
 fn sanitize_output(input: &str) -> String {
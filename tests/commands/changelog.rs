@@ -672,3 +672,642 @@ fn merges_with_first_parent_skips_branch_commits() -> Result<(), Box<dyn std::er
 
     Ok(())
 }
+
+#[test]
+fn all_writes_the_changelog_of_every_package() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_monorepo()?;
+    let repo = temp.path();
+
+    run_convco_command(&["changelog", "--all"], Some(repo), true, "")?;
+
+    insta::assert_snapshot!(
+        "changelog_all_app",
+        sanitize_output(&fs::read_to_string(repo.join("packages/app/CHANGELOG.md"))?)
+    );
+    insta::assert_snapshot!(
+        "changelog_all_lib",
+        sanitize_output(&fs::read_to_string(repo.join("LIB_CHANGELOG.md"))?)
+    );
+
+    // the data formats are printed, not written to the changelog files
+    let output = run_convco_command(
+        &["changelog", "--all", "--format", "json"],
+        Some(repo),
+        false,
+        "",
+    )?;
+    assert!(output.contains("cannot be used with"), "got:\n{output}");
+    assert!(fs::read_to_string(repo.join("LIB_CHANGELOG.md"))?.starts_with("# Changelog"));
+
+    // the changelogs are relative to the root of the repository, not to the current directory
+    let app = fs::read_to_string(repo.join("packages/app/CHANGELOG.md"))?;
+    let lib = fs::read_to_string(repo.join("LIB_CHANGELOG.md"))?;
+    fs::remove_file(repo.join("packages/app/CHANGELOG.md"))?;
+    fs::remove_file(repo.join("LIB_CHANGELOG.md"))?;
+    run_convco_command(
+        &["changelog", "--all", "--config", "../../.convco"],
+        Some(&repo.join("packages/app")),
+        true,
+        "",
+    )?;
+    assert_eq!(
+        fs::read_to_string(repo.join("packages/app/CHANGELOG.md"))?,
+        app
+    );
+    assert_eq!(fs::read_to_string(repo.join("LIB_CHANGELOG.md"))?, lib);
+    assert!(!repo.join("packages/app/LIB_CHANGELOG.md").exists());
+
+    Ok(())
+}
+
+#[test]
+fn incremental_prepends_new_versions_and_keeps_older_sections(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_version_tags()?;
+    let repo = temp.path();
+    let hand_edited = "## v2.0.0 (2020-01-01)\n\n* Hand edited *second* feature.\n\n## v1.0.0\n\nSee the [announcement](https://example.com).\n";
+    fs::write(
+        repo.join("CHANGELOG.md"),
+        format!("# Changelog\n\n## Unreleased\n\n* stale entry\n\n{hand_edited}"),
+    )?;
+
+    run_convco_command(
+        &["changelog", "--incremental", "--output", "CHANGELOG.md"],
+        Some(repo),
+        true,
+        "",
+    )?;
+
+    let changelog = fs::read_to_string(repo.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.ends_with(&format!(")\n\n{hand_edited}")),
+        "got:\n{changelog}"
+    );
+    assert!(!changelog.contains("stale entry"), "got:\n{changelog}");
+    insta::assert_snapshot!(
+        "changelog_incremental_prepends_new_versions",
+        sanitize_output(&changelog)
+    );
+
+    // nothing new: only the unreleased section is regenerated
+    run_convco_command(
+        &["changelog", "--prepend", "--output", "CHANGELOG.md"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert_eq!(fs::read_to_string(repo.join("CHANGELOG.md"))?, changelog);
+
+    Ok(())
+}
+
+#[test]
+fn incremental_keeps_a_hand_edited_preamble() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_version_tags()?;
+    let repo = temp.path();
+    let preamble = "# Release history\n\nAll notable changes, see [the docs](https://example.com).\n<!-- keep -->\n\n";
+    fs::write(
+        repo.join("CHANGELOG.md"),
+        format!("{preamble}## Unreleased\n\n* stale entry\n\n## v2.0.0 (2020-01-01)\n\n* second\n"),
+    )?;
+
+    run_convco_command(
+        &["changelog", "--incremental", "--output", "CHANGELOG.md"],
+        Some(repo),
+        true,
+        "",
+    )?;
+
+    let changelog = fs::read_to_string(repo.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.starts_with(&format!("{preamble}## ")),
+        "got:\n{changelog}"
+    );
+    assert!(!changelog.contains("# Changelog"), "got:\n{changelog}");
+    assert!(!changelog.contains("stale entry"), "got:\n{changelog}");
+    assert!(
+        changelog.ends_with("## v2.0.0 (2020-01-01)\n\n* second\n"),
+        "got:\n{changelog}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn incremental_requires_output_file() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: initial"])?;
+
+    run_convco_command(
+        &["changelog", "--incremental"],
+        Some(temp.path()),
+        false,
+        "changelog_incremental_requires_output_file",
+    )?;
+
+    Ok(())
+}
+
+const SCOPED_COMMITS: [&str; 5] = [
+    "feat(ui): dark mode",
+    "fix(api): crash",
+    "feat: global setting",
+    "feat(API): add endpoint",
+    "fix(ui): button",
+];
+
+#[test]
+fn group_by_type_scope_nests_scopes_in_sections() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&SCOPED_COMMITS)?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "groupBy: typeScope\nscopeOrder: [ui]\n",
+    )?;
+
+    run_convco_command(
+        &["changelog", "--no-links"],
+        Some(repo),
+        true,
+        "changelog_group_by_type_scope",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn mixed_case_scopes_have_one_spelling() -> Result<(), Box<dyn std::error::Error>> {
+    let temp =
+        setup_repo_with_commits(&["feat(Api): first", "fix(api): second", "feat(API): third"])?;
+    let repo = temp.path();
+    fs::write(repo.join(".convco"), "groupBy: typeScope\n")?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert_eq!(output.matches("#### API").count(), 2, "got:\n{output}");
+    assert!(!output.contains("Api"), "got:\n{output}");
+    assert!(!output.contains("#### api"), "got:\n{output}");
+
+    fs::write(
+        repo.join(".convco"),
+        "groupBy: typeScope\nscopeOrder: [api]\n",
+    )?;
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert_eq!(output.matches("#### api").count(), 2, "got:\n{output}");
+    assert!(!output.contains("API"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn group_by_scope_type_uses_scope_sections() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&SCOPED_COMMITS)?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "groupBy: scopeType\nunscopedSection: Other\n",
+    )?;
+
+    let output = run_convco_command(
+        &["changelog", "--no-links"],
+        Some(repo),
+        true,
+        "changelog_group_by_scope_type",
+    )?;
+
+    let api = output.find("### API").expect("api section");
+    let ui = output.find("### ui").expect("ui section");
+    let other = output.find("### Other").expect("unscoped section");
+    assert!(api < ui && ui < other, "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn format_json_serializes_every_version() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_version_tags()?;
+    let repo = temp.path();
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix(parser): handle empty input\n\nThe body.\n\nBREAKING CHANGE: empty input is an error\nCloses #12\nCo-authored-by: Octo Cat <1+octocat@users.noreply.github.com>",
+        ],
+    )?;
+
+    let mut cmd = Command::cargo_bin("convco")?;
+    let assert = cmd
+        .current_dir(repo)
+        .args(["changelog", "--format", "json", "--max-versions", "2"])
+        .assert()
+        .success();
+    let versions: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+
+    assert_eq!(
+        versions.as_array().map(Vec::len),
+        Some(2),
+        "got:\n{versions:#}"
+    );
+    let unreleased = &versions[0];
+    assert_eq!(unreleased["version"], "Unreleased");
+    assert_eq!(unreleased["previousTag"], "v3.0.0");
+    assert_eq!(unreleased["linkCompare"], true);
+    let fix = &unreleased["commitGroups"][1]["commits"][0];
+    assert_eq!(unreleased["commitGroups"][1]["title"], "Fixes");
+    assert_eq!(fix["scope"], "parser");
+    assert_eq!(fix["subject"], "handle empty input");
+    assert_eq!(fix["body"], "The body.");
+    assert_eq!(fix["references"][0]["issue"], "12");
+    assert_eq!(
+        unreleased["noteGroups"][0]["notes"][0]["text"],
+        "empty input is an error"
+    );
+    assert_eq!(fix["author"]["name"], "Convco Test");
+    assert_eq!(fix["committer"]["email"], "test@example.com");
+    assert_eq!(fix["coAuthors"][0]["user"], "octocat");
+    let contributors: Vec<&str> = unreleased["contributors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|person| person["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(contributors, ["Convco Test", "Octo Cat"]);
+    assert_eq!(versions[1]["version"], "v3.0.0");
+
+    Ok(())
+}
+
+#[test]
+fn co_authors_are_resolved_through_the_mailmap() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: initial"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".mailmap"),
+        "Jane Doe <jane@example.com> <jane@old.example.com>\n",
+    )?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix: pair\n\nCo-authored-by: jane <jane@old.example.com>",
+        ],
+    )?;
+
+    let mut cmd = Command::cargo_bin("convco")?;
+    let assert = cmd
+        .current_dir(repo)
+        .args(["changelog", "--format", "json"])
+        .assert()
+        .success();
+    let versions: serde_json::Value = serde_json::from_slice(&assert.get_output().stdout)?;
+
+    let fix = &versions[0]["commitGroups"][1]["commits"][0];
+    assert_eq!(fix["subject"], "pair", "got:\n{versions:#}");
+    assert_eq!(fix["coAuthors"][0]["name"], "Jane Doe");
+    assert_eq!(fix["coAuthors"][0]["email"], "jane@example.com");
+    let contributors: Vec<&str> = versions[0]["contributors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|person| person["name"].as_str().unwrap())
+        .collect();
+    assert_eq!(contributors, ["Convco Test", "Jane Doe"]);
+
+    Ok(())
+}
+
+#[test]
+fn format_yaml() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat(api): add endpoint", "fix: crash"])?;
+
+    // parsed rather than snapshotted: the serializer quotes hashes that are all digits
+    let mut cmd = Command::cargo_bin("convco")?;
+    let assert = cmd
+        .current_dir(temp.path())
+        .args(["changelog", "--format", "yaml", "--no-links"])
+        .assert()
+        .success();
+    let versions: serde_json::Value = serde_norway::from_slice(&assert.get_output().stdout)?;
+
+    assert_eq!(
+        versions.as_array().map(Vec::len),
+        Some(1),
+        "got:\n{versions:#}"
+    );
+    let unreleased = &versions[0];
+    assert_eq!(unreleased["version"], "Unreleased");
+    assert_eq!(unreleased["previousTag"], "");
+    assert_eq!(unreleased["linkReferences"], false);
+    assert_eq!(unreleased["commitGroups"][0]["title"], "Features");
+    let feat = &unreleased["commitGroups"][0]["commits"][0];
+    assert_eq!(feat["scope"], "api");
+    assert_eq!(feat["subject"], "add endpoint");
+    assert_eq!(feat["shortHash"].as_str().map(str::len), Some(7));
+    assert_eq!(unreleased["commitGroups"][1]["title"], "Fixes");
+    let fix = &unreleased["commitGroups"][1]["commits"][0];
+    assert_eq!(fix["scope"], serde_json::Value::Null);
+    assert_eq!(fix["subject"], "crash");
+    assert_eq!(unreleased["contributors"][0]["name"], "Convco Test");
+
+    Ok(())
+}
+
+#[test]
+fn scope_aliases_use_the_scope_name() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat(cmd): alias", "fix(CLI): other casing"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "scopes:\n- name: cli\n  aliases: [cmd]\n",
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+
+    assert!(output.contains("**cli:** alias"), "got:\n{output}");
+    assert!(output.contains("**cli:** other casing"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn calver_tags_are_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "versionScheme:\n  type: calver\n  format: YYYY.WW\n",
+    )?;
+    git(repo, &["tag", "v2026.9"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: second"])?;
+    git(repo, &["tag", "v2026.42"])?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+
+    let second = output.find("## v2026.42").expect("a v2026.42 section");
+    let first = output.find("## v2026.9").expect("a v2026.9 section");
+    assert!(second < first, "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn release_notes_render_a_single_version() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_version_tags()?;
+    let repo = temp.path();
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix(api): crash, thanks @octocat\n\nCloses #12",
+        ],
+    )?;
+    git(repo, &["tag", "v3.0.1"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "fix: unreleased"])?;
+
+    run_convco_command(
+        &["changelog", "--release-notes", "v3.0.1"],
+        Some(repo),
+        true,
+        "changelog_release_notes",
+    )?;
+
+    // the hosting platform links issues, commits and mentions by itself
+    git(
+        repo,
+        &[
+            "remote",
+            "add",
+            "origin",
+            "https://github.com/owner/repo.git",
+        ],
+    )?;
+    let output = run_convco_command(
+        &["changelog", "--release-notes", "v3.0.1"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(
+        output.contains("* **api:** crash, thanks @octocat (<OID>), closes #12\n"),
+        "got:\n{output}"
+    );
+
+    // with links on other hosts
+    fs::write(repo.join(".convco"), "host: https://git.example.com\n")?;
+    let output = run_convco_command(
+        &["changelog", "--release-notes", "v3.0.1"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(
+        output.contains("thanks [@octocat](https://git.example.com/octocat) ([<OID>]("),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn release_as_footer_titles_the_unreleased_section() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "feat: second\n\nRelease-As: 3.0.0",
+        ],
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert!(output.contains("## v3.0.0 ("), "got:\n{output}");
+    assert!(!output.contains("Unreleased"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn reverts_cancel_commits_of_the_same_version() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    let commit_file = |name: &str, message: &str| -> Result<(), Box<dyn std::error::Error>> {
+        fs::write(repo.join(name), message)?;
+        git(repo, &["add", name])?;
+        git(repo, &["commit", "-m", message])
+    };
+    commit_file("old.txt", "feat: old")?;
+    git(repo, &["tag", "v1.0.0"])?;
+    commit_file("kept.txt", "feat: kept")?;
+    commit_file("dropped.txt", "feat: dropped")?;
+    git(repo, &["revert", "--no-edit", "HEAD"])?;
+    git(repo, &["revert", "--no-edit", "v1.0.0"])?;
+    git(
+        repo,
+        &["commit", "--allow-empty", "-m", "revert: feat: manual"],
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    let unreleased = &output[..output.find("## v1.0.0").expect("a v1.0.0 section")];
+    assert!(unreleased.contains("* kept"), "got:\n{output}");
+    assert!(!unreleased.contains("dropped"), "got:\n{output}");
+    let reverts = unreleased.find("### Reverts").expect("a Reverts section");
+    assert!(
+        unreleased.find("### Features").unwrap() < reverts,
+        "got:\n{output}"
+    );
+    assert!(
+        unreleased[reverts..].contains("* feat: old"),
+        "got:\n{output}"
+    );
+    assert!(
+        unreleased[reverts..].contains("* feat: manual"),
+        "got:\n{output}"
+    );
+
+    let output = run_convco_command(
+        &["changelog", "--no-links", "--ignore-reverts"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(!output.contains("### Reverts"), "got:\n{output}");
+    assert!(output.contains("* dropped"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn squash_handling_expands_squash_commits_and_reads_pull_request_titles(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    fs::write(repo.join(".convco"), "squashHandling: expand\n")?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "feat: squashed (#12)\n\n* feat(api): add endpoint\n\n* fix: crash\n\n* wip",
+        ],
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    let line = |text: &str| {
+        output
+            .lines()
+            .find(|line| line.contains(text))
+            .unwrap_or("")
+    };
+    assert!(line("add endpoint").contains("#12"), "got:\n{output}");
+    assert!(line("crash").contains("#12"), "got:\n{output}");
+    assert!(!output.contains("squashed"), "got:\n{output}");
+
+    fs::write(
+        repo.join(".convco"),
+        "squashHandling: prTitle\nfirstParent: true\n",
+    )?;
+    git(repo, &["checkout", "-q", "-b", "topic"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "wip"])?;
+    git(repo, &["checkout", "-q", "-"])?;
+    git(
+        repo,
+        &[
+            "merge",
+            "--no-ff",
+            "topic",
+            "-m",
+            "Merge pull request #42 from owner/topic\n\nfeat: pull request title",
+        ],
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    let line = |text: &str| {
+        output
+            .lines()
+            .find(|line| line.contains(text))
+            .unwrap_or("")
+    };
+    assert!(line("pull request title").contains("#42"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn changelog_footers_skip_rewrite_and_move_entries() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&[
+        "feat: kept",
+        "feat: internal\n\nChangelog: skip",
+        "fix: bump openssl\n\nChangelog-Entry: fix CVE-2024-0001 in TLS\nChangelog-Section: Security",
+        "chore: hidden\n\nchangelog-section: Security",
+        "fix: typo\n\nRelease-Note: skip",
+    ])?;
+    let repo = temp.path();
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert!(output.contains("* kept"), "got:\n{output}");
+    assert!(!output.contains("internal"), "got:\n{output}");
+    assert!(!output.contains("bump openssl"), "got:\n{output}");
+    let security = output.find("### Security").expect("a Security section");
+    assert!(
+        output.find("### Fixes").unwrap() < security,
+        "got:\n{output}"
+    );
+    assert!(
+        output[security..].contains("* fix CVE-2024-0001 in TLS"),
+        "got:\n{output}"
+    );
+    assert!(output[security..].contains("* hidden"), "got:\n{output}");
+
+    fs::write(
+        repo.join(".convco"),
+        "changelogFooters:\n  skip: Release-Note\n",
+    )?;
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert!(output.contains("* internal"), "got:\n{output}");
+    assert!(!output.contains("typo"), "got:\n{output}");
+    assert!(
+        output.contains("* fix CVE-2024-0001 in TLS"),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn expand_keeps_a_breaking_squash_subject() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    fs::write(repo.join(".convco"), "squashHandling: expand\n")?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "feat(api)!: drop v1 endpoints (#12)\n\n* fix: handle null\n\n* docs: update\n\nBREAKING CHANGE: the v1 endpoints are gone",
+        ],
+    )?;
+
+    let output = run_convco_command(&["changelog", "--no-links"], Some(repo), true, "")?;
+    assert!(
+        output.contains("* **api:** drop v1 endpoints (#12)"),
+        "got:\n{output}"
+    );
+    assert!(output.contains("handle null"), "got:\n{output}");
+    assert!(output.contains("### ⚠ BREAKING CHANGE"), "got:\n{output}");
+    assert!(
+        output.contains("* the v1 endpoints are gone"),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
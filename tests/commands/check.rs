@@ -303,3 +303,264 @@ fn strip_removes_comments_from_stdin() -> Result<(), Box<dyn std::error::Error>>
 
     Ok(())
 }
+
+#[test]
+fn json_output_reports_each_commit_and_summary() -> Result<(), Box<dyn std::error::Error>> {
+    let temp =
+        setup_repo_with_commits(&["feat: valid", "wip: still deciding", "not conventional"])?;
+    let repo = temp.path();
+
+    run_convco_command(
+        &["check", "--output-format", "json"],
+        Some(repo),
+        false,
+        "check_json_output_reports_each_commit_and_summary",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn junit_output_reports_each_commit() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: valid"])?;
+    let repo = temp.path();
+
+    let output = run_convco_command(&["check", "--output-format", "junit"], Some(repo), true, "")?;
+
+    assert!(
+        output.contains(r#"<testsuite name="convco check" tests="1" failures="0">"#),
+        "got:\n{output}"
+    );
+    assert!(!output.contains("no errors in"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn lint_rules_report_errors_and_warnings() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&[
+        "feat: add a feature",
+        "fix: Handle the error.",
+        "docs: a header that is definitely too long",
+    ])?;
+    let repo = temp.path();
+    std::fs::write(
+        repo.join(".convco"),
+        "rules:\n  subjectCase: { level: error, case: lower }\n  headerMaxLength: { level: error, max: 40 }\n  subjectFullStop: { level: warning }\n",
+    )?;
+
+    run_convco_command(
+        &["check"],
+        Some(repo),
+        false,
+        "check_lint_rules_report_errors_and_warnings",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn lint_rule_warnings_do_not_fail() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["fix: handle the error."])?;
+    let repo = temp.path();
+    std::fs::write(
+        repo.join(".convco"),
+        "rules:\n  subjectFullStop: { level: warning }\n",
+    )?;
+
+    run_convco_command(
+        &["check"],
+        Some(repo),
+        true,
+        "check_lint_rule_warnings_do_not_fail",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn pre_push_checks_only_commits_missing_on_the_remote() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["not conventional", "feat: pushed"])?;
+    let repo = temp.path();
+    git(repo, &["update-ref", "refs/remotes/origin/main", "HEAD"])?;
+    let remote_sha = String::from_utf8(
+        StdCommand::new("git")
+            .args(["rev-parse", "HEAD"])
+            .current_dir(repo)
+            .output()?
+            .stdout,
+    )?;
+    let remote_sha = remote_sha.trim();
+    git(repo, &["commit", "--allow-empty", "-m", "fix: new"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "bad new"])?;
+    let zero = "0".repeat(remote_sha.len());
+
+    let pre_push = |stdin: String| -> Result<String, Box<dyn std::error::Error>> {
+        let assert = Command::cargo_bin("convco")?
+            .current_dir(repo)
+            .args(["check", "--pre-push"])
+            .write_stdin(stdin)
+            .assert()
+            .failure();
+        Ok(String::from_utf8(assert.get_output().stdout.clone())?)
+    };
+
+    // new branch: everything not on a remote-tracking branch
+    let output = pre_push(format!("refs/heads/topic HEAD refs/heads/topic {zero}\n"))?;
+    assert!(output.contains("bad new"), "{output}");
+    assert!(output.contains("1/2 failed"), "{output}");
+
+    // existing branch: remote..local
+    let output = pre_push(format!(
+        "refs/heads/main HEAD refs/heads/main {remote_sha}\n"
+    ))?;
+    assert!(output.contains("1/2 failed"), "{output}");
+
+    // deleted refs are skipped
+    Command::cargo_bin("convco")?
+        .current_dir(repo)
+        .args(["check", "--pre-push"])
+        .write_stdin(format!("(delete) {zero} refs/heads/old {remote_sha}\n"))
+        .assert()
+        .success();
+
+    Ok(())
+}
+
+#[test]
+fn unknown_scope_suggests_a_known_scope() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&[
+        "feat(parser): known scope",
+        "fix(cmd): alias of cli",
+        "fix: no scope",
+        "feat(chnagelog): typo",
+    ])?;
+    let repo = temp.path();
+    std::fs::write(
+        repo.join(".convco"),
+        "scopes:\n- name: parser\n- name: changelog\n- name: cli\n  aliases: [cmd]\n",
+    )?;
+
+    run_convco_command(
+        &["check"],
+        Some(repo),
+        false,
+        "check_unknown_scope_suggests_a_known_scope",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn allow_fixups_checks_the_target_in_the_range() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: parse x"])?;
+    git(repo, &["commit", "--allow-empty", "--fixup", "HEAD"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "squash! fixup! feat: parse x",
+        ],
+    )?;
+
+    let output = run_convco_command(&["check", "v1.0.0..HEAD"], Some(repo), false, "")?;
+    assert!(output.contains("2/3 failed"), "got:\n{output}");
+    let output = run_convco_command(
+        &["check", "--allow-fixups", "v1.0.0..HEAD"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("no errors in 3 commits"), "got:\n{output}");
+
+    git(repo, &["commit", "--allow-empty", "--fixup", "v1.0.0"])?;
+    let output = run_convco_command(
+        &["check", "--allow-fixups", "v1.0.0..HEAD"],
+        Some(repo),
+        false,
+        "",
+    )?;
+    assert!(
+        output.contains("target of the fixup is not in the checked range: feat: base"),
+        "got:\n{output}"
+    );
+    assert!(output.contains("1/4 failed"), "got:\n{output}");
+
+    Ok(())
+}
+
+#[test]
+fn allow_fixups_finds_unchecked_targets_and_checks_amend_messages(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "wip: parse x"])?;
+    git(
+        repo,
+        &["commit", "--allow-empty", "-m", "fixup! wip: parse x"],
+    )?;
+
+    // the target is left out by the ignore pattern and by -n
+    let output = run_convco_command(
+        &[
+            "check",
+            "--allow-fixups",
+            "--ignore-message-pattern",
+            "^wip",
+            "v1.0.0..HEAD",
+        ],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("no errors in 1 commit"), "got:\n{output}");
+    let output = run_convco_command(
+        &["check", "--allow-fixups", "-n", "1", "v1.0.0..HEAD"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("no errors in 1 commit"), "got:\n{output}");
+
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "amend! wip: parse x\n\nfeat: parse x",
+        ],
+    )?;
+    let output = run_convco_command(
+        &["check", "--allow-fixups", "-n", "1", "v1.0.0..HEAD"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("no errors in 1 commit"), "got:\n{output}");
+
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "amend! wip: parse x\n\nparse x",
+        ],
+    )?;
+    let output = run_convco_command(
+        &["check", "--allow-fixups", "-n", "1", "v1.0.0..HEAD"],
+        Some(repo),
+        false,
+        "",
+    )?;
+    assert!(output.contains("1/1 failed"), "got:\n{output}");
+
+    Ok(())
+}
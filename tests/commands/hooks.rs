@@ -0,0 +1,86 @@
+use std::{env, fs, path::Path, process::Command as StdCommand};
+
+use super::super::{git, run_convco_command, setup_repo_with_commits};
+
+/// Runs `git commit` with the convco binary under test on the `PATH`.
+fn commit_with_hooks(repo: &Path, message: &str) -> Result<bool, Box<dyn std::error::Error>> {
+    let bin = assert_cmd::cargo::cargo_bin("convco");
+    let path = env::join_paths(
+        bin.parent()
+            .into_iter()
+            .map(Path::to_path_buf)
+            .chain(env::split_paths(&env::var_os("PATH").unwrap_or_default())),
+    )?;
+    let output = StdCommand::new("git")
+        .args(["commit", "--allow-empty", "-m", message])
+        .env("PATH", path)
+        .current_dir(repo)
+        .output()?;
+    Ok(output.status.success())
+}
+
+#[test]
+fn install_is_idempotent_and_checks_commits() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+
+    run_convco_command(&["hooks", "install"], Some(repo), true, "")?;
+    let hook = fs::read_to_string(repo.join(".git/hooks/commit-msg"))?;
+    run_convco_command(&["hooks", "install"], Some(repo), true, "")?;
+    assert_eq!(
+        fs::read_to_string(repo.join(".git/hooks/commit-msg"))?,
+        hook
+    );
+    assert!(!repo.join(".git/hooks/commit-msg.convco-chained").exists());
+    assert!(!repo.join(".git/hooks/pre-push").exists());
+
+    run_convco_command(
+        &["hooks", "status"],
+        Some(repo),
+        true,
+        "hooks_status_installed",
+    )?;
+
+    assert!(commit_with_hooks(repo, "feat: second")?);
+    assert!(!commit_with_hooks(repo, "not conventional")?);
+
+    run_convco_command(&["hooks", "uninstall"], Some(repo), true, "")?;
+    assert!(!repo.join(".git/hooks/commit-msg").exists());
+    assert!(commit_with_hooks(repo, "not conventional")?);
+
+    Ok(())
+}
+
+#[test]
+fn existing_hooks_are_chained_and_restored() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    let hooks = repo.join("custom-hooks");
+    git(repo, &["config", "core.hooksPath", "custom-hooks"])?;
+    fs::create_dir_all(&hooks)?;
+    let existing = "#!/bin/sh\necho existing > \"$(git rev-parse --git-dir)/existing-ran\"\n";
+    fs::write(hooks.join("commit-msg"), existing)?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(hooks.join("commit-msg"), fs::Permissions::from_mode(0o755))?;
+    }
+
+    run_convco_command(&["hooks", "install", "--pre-push"], Some(repo), true, "")?;
+    run_convco_command(&["hooks", "install", "--pre-push"], Some(repo), true, "")?;
+    assert_eq!(
+        fs::read_to_string(hooks.join("commit-msg.convco-chained"))?,
+        existing
+    );
+    assert!(hooks.join("pre-push").exists());
+
+    assert!(commit_with_hooks(repo, "fix: chained")?);
+    assert!(repo.join(".git/existing-ran").exists());
+
+    run_convco_command(&["hooks", "uninstall"], Some(repo), true, "")?;
+    assert_eq!(fs::read_to_string(hooks.join("commit-msg"))?, existing);
+    assert!(!hooks.join("commit-msg.convco-chained").exists());
+    assert!(!hooks.join("pre-push").exists());
+
+    Ok(())
+}
@@ -2,4 +2,6 @@ mod changelog;
 mod check;
 mod commit;
 mod config;
+mod hooks;
+mod release;
 mod version;
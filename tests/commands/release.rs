@@ -0,0 +1,217 @@
+use std::{fs, path::Path, process::Command as StdCommand};
+
+use super::super::{git, run_convco_command, setup_repo_with_commits};
+
+fn git_output(repo: &Path, args: &[&str]) -> Result<String, Box<dyn std::error::Error>> {
+    let output = StdCommand::new("git")
+        .args(args)
+        .current_dir(repo)
+        .output()?;
+    Ok(String::from_utf8(output.stdout)?.trim_end().to_owned())
+}
+
+fn setup_release_repo() -> Result<tempfile::TempDir, Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    fs::write(
+        repo.join("CHANGELOG.md"),
+        "# Changelog\n\n## v1.0.0\n\n* first\n",
+    )?;
+    git(repo, &["add", "CHANGELOG.md"])?;
+    git(repo, &["commit", "-m", "docs: add changelog"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: second"])?;
+    Ok(temp)
+}
+
+#[test]
+fn dry_run_prints_section_commit_and_tag() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_release_repo()?;
+    let repo = temp.path();
+
+    run_convco_command(
+        &["release", "--dry-run"],
+        Some(repo),
+        true,
+        "release_dry_run_prints_section_commit_and_tag",
+    )?;
+
+    assert_eq!(git_output(repo, &["tag", "--list"])?, "v1.0.0");
+    assert_eq!(
+        fs::read_to_string(repo.join("CHANGELOG.md"))?,
+        "# Changelog\n\n## v1.0.0\n\n* first\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn release_prepends_changelog_commits_and_tags() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_release_repo()?;
+    let repo = temp.path();
+
+    fs::write(repo.join(".convco"), "bumpFiles:\n  - path: VERSION\n")?;
+    fs::write(repo.join("VERSION"), "1.0.0\n")?;
+    git(repo, &["add", ".convco", "VERSION"])?;
+    git(repo, &["commit", "-m", "feat: bump files"])?;
+
+    run_convco_command(&["release"], Some(repo), true, "")?;
+
+    assert_eq!(fs::read_to_string(repo.join("VERSION"))?, "1.1.0\n");
+    assert_eq!(git_output(repo, &["status", "--porcelain"])?, "");
+    let changelog = fs::read_to_string(repo.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.starts_with("# Changelog\n\n## [v1.1.0]("),
+        "got:\n{changelog}"
+    );
+    assert!(changelog.contains("* second ("), "got:\n{changelog}");
+    assert!(
+        changelog.ends_with("\n## v1.0.0\n\n* first\n"),
+        "got:\n{changelog}"
+    );
+    assert_eq!(
+        git_output(repo, &["log", "-1", "--format=%s"])?,
+        "chore(release): v1.1.0"
+    );
+    assert_eq!(
+        git_output(repo, &["cat-file", "-t", "v1.1.0"])?,
+        "tag",
+        "release tag should be annotated"
+    );
+    assert_eq!(
+        git_output(repo, &["rev-parse", "v1.1.0^{commit}"])?,
+        git_output(repo, &["rev-parse", "HEAD"])?
+    );
+
+    run_convco_command(
+        &["release"],
+        Some(repo),
+        false,
+        "release_fails_without_new_commits",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn release_commit_leaves_out_other_staged_changes() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_release_repo()?;
+    let repo = temp.path();
+    fs::write(repo.join("notes.txt"), "draft\n")?;
+    git(repo, &["add", "notes.txt"])?;
+
+    run_convco_command(&["release"], Some(repo), true, "")?;
+
+    assert_eq!(
+        git_output(repo, &["show", "--name-only", "--format=", "HEAD"])?,
+        "CHANGELOG.md"
+    );
+    assert_eq!(
+        git_output(repo, &["status", "--porcelain"])?,
+        "A  notes.txt"
+    );
+    assert_eq!(git_output(repo, &["describe", "--tags"])?, "v1.1.0");
+
+    Ok(())
+}
+
+#[test]
+fn no_commit_and_no_tag_only_update_the_changelog() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_release_repo()?;
+    let repo = temp.path();
+    let head = git_output(repo, &["rev-parse", "HEAD"])?;
+
+    run_convco_command(
+        &["release", "--no-commit", "--no-tag", "--major"],
+        Some(repo),
+        true,
+        "",
+    )?;
+
+    assert_eq!(git_output(repo, &["rev-parse", "HEAD"])?, head);
+    assert_eq!(git_output(repo, &["tag", "--list"])?, "v1.0.0");
+    let changelog = fs::read_to_string(repo.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.starts_with("# Changelog\n\n## [v2.0.0]("),
+        "got:\n{changelog}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn release_ignores_the_environment_of_version_and_changelog(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_release_repo()?;
+    let repo = temp.path();
+
+    assert_cmd::Command::cargo_bin("convco")?
+        .current_dir(repo)
+        .args(["release", "--no-commit", "--no-tag"])
+        .env("CONVCO_FORMAT", "json")
+        .env("CONVCO_MAX_VERSIONS", "0")
+        .env("CONVCO_FIRST_PARENT", "true")
+        .assert()
+        .success();
+
+    let changelog = fs::read_to_string(repo.join("CHANGELOG.md"))?;
+    assert!(
+        changelog.starts_with("# Changelog\n\n## [v1.1.0]("),
+        "got:\n{changelog}"
+    );
+    assert!(changelog.contains("* second ("), "got:\n{changelog}");
+
+    Ok(())
+}
+
+#[test]
+fn regex_scheme_keeps_the_tag_names() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: first"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "versionScheme:\n  type: regex\n  pattern: '^r(\\d+)-(\\d+)$'\n",
+    )?;
+    git(repo, &["tag", "r1-0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: second"])?;
+    git(repo, &["tag", "r1-1"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: third"])?;
+
+    let output = run_convco_command(
+        &["changelog", "--prefix", "", "--no-links"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("## r1-1"), "got:\n{output}");
+    assert!(output.contains("## r1-0"), "got:\n{output}");
+    assert!(output.contains("* second"), "got:\n{output}");
+
+    // `1.2` is not a tag of the scheme
+    let output = run_convco_command(
+        &["release", "--prefix", "", "--dry-run"],
+        Some(repo),
+        false,
+        "",
+    )?;
+    assert!(
+        output.contains("the tag `1.2` of version 1.2 does not match"),
+        "got:\n{output}"
+    );
+
+    fs::write(
+        repo.join(".convco"),
+        "versionScheme:\n  type: regex\n  pattern: '^r(\\d+)-(\\d+)$'\ntagTemplate: 'r{{major}}-{{minor}}'\n",
+    )?;
+    let output = run_convco_command(
+        &["release", "--prefix", "", "--dry-run"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.contains("tag: r1-2"), "got:\n{output}");
+    assert!(output.contains("* third"), "got:\n{output}");
+    assert!(!output.contains("* second"), "got:\n{output}");
+
+    Ok(())
+}
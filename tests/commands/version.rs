@@ -4,7 +4,8 @@ use assert_cmd::Command;
 use tempfile::tempdir;
 
 use super::super::{
-    git, run_convco_command, setup_repo_with_commits, setup_repo_with_non_linear_version_tags,
+    git, run_convco_command, setup_monorepo, setup_repo_with_commits,
+    setup_repo_with_non_linear_version_tags,
 };
 
 fn assert_version(
@@ -456,3 +457,552 @@ fn mixed_excluded_and_included_commit_is_used_for_bump() -> Result<(), Box<dyn s
 
     Ok(())
 }
+
+#[test]
+fn all_prints_the_version_of_every_package() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_monorepo()?;
+    let repo = temp.path();
+
+    assert_version(repo, &["version", "--all"], "app 1.0.0\nlib 0.1.0")?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--all"],
+        "app 1.0.1\nlib 0.1.1",
+    )?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--all", "--print-prefix"],
+        "app app-v1.0.1\nlib lib@0.1.1",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn all_fails_without_packages() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+
+    run_convco_command(
+        &["version", "--all"],
+        Some(temp.path()),
+        false,
+        "version_all_fails_without_packages",
+    )?;
+
+    Ok(())
+}
+
+#[test]
+fn write_updates_the_bump_files() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: next"])?;
+    fs::create_dir_all(repo.join("crates/core"))?;
+    fs::write(
+        repo.join(".convco"),
+        "bumpFiles:\n  - path: Cargo.toml\n  - path: package.json\n  - path: VERSION\n",
+    )?;
+    fs::write(
+        repo.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+    )?;
+    fs::write(
+        repo.join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    )?;
+    fs::write(
+        repo.join("Cargo.lock"),
+        "[[package]]\nname = \"core\"\nversion = \"1.0.0\"\n\n[[package]]\nname = \"dep\"\nversion = \"1.0.0\"\n",
+    )?;
+    fs::write(
+        repo.join("package.json"),
+        "{\n  \"name\": \"app\",\n  \"version\": \"1.0.0\"\n}\n",
+    )?;
+    fs::write(repo.join("VERSION"), "1.0.0\n")?;
+
+    assert_version(repo, &["version", "--bump", "--write"], "1.1.0")?;
+
+    assert_eq!(
+        fs::read_to_string(repo.join("Cargo.toml"))?,
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.1.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("Cargo.lock"))?,
+        "[[package]]\nname = \"core\"\nversion = \"1.1.0\"\n\n[[package]]\nname = \"dep\"\nversion = \"1.0.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("package.json"))?,
+        "{\n  \"name\": \"app\",\n  \"version\": \"1.1.0\"\n}\n"
+    );
+    assert_eq!(fs::read_to_string(repo.join("VERSION"))?, "1.1.0\n");
+
+    Ok(())
+}
+
+#[test]
+fn write_updates_the_package_and_the_workspace_from_the_repository_root(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: next"])?;
+    fs::create_dir_all(repo.join("crates/core"))?;
+    fs::write(repo.join(".convco"), "bumpFiles:\n  - path: Cargo.toml\n")?;
+    fs::write(
+        repo.join("Cargo.toml"),
+        "[package]\nname = \"app\"\nversion = \"1.0.0\"\n\n[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n",
+    )?;
+    fs::write(
+        repo.join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    )?;
+    fs::write(
+        repo.join("Cargo.lock"),
+        "[[package]]\nname = \"app\"\nversion = \"1.0.0\"\n\n[[package]]\nname = \"core\"\nversion = \"1.0.0\"\n",
+    )?;
+
+    assert_version(
+        &repo.join("crates/core"),
+        &["-c", "../../.convco", "version", "--bump", "--write"],
+        "1.1.0",
+    )?;
+
+    assert_eq!(
+        fs::read_to_string(repo.join("Cargo.toml"))?,
+        "[package]\nname = \"app\"\nversion = \"1.1.0\"\n\n[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.1.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("Cargo.lock"))?,
+        "[[package]]\nname = \"app\"\nversion = \"1.1.0\"\n\n[[package]]\nname = \"core\"\nversion = \"1.1.0\"\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn write_updates_the_requirements_between_workspace_members(
+) -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: next"])?;
+    fs::create_dir_all(repo.join("crates/core"))?;
+    fs::create_dir_all(repo.join("crates/cli"))?;
+    fs::write(repo.join(".convco"), "bumpFiles:\n  - path: Cargo.toml\n")?;
+    fs::write(
+        repo.join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.0.0\"\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"1.0.0\" }\ndep = \"1.0.0\"\n",
+    )?;
+    fs::write(
+        repo.join("crates/core/Cargo.toml"),
+        "[package]\nname = \"core\"\nversion.workspace = true\n",
+    )?;
+    fs::write(
+        repo.join("crates/cli/Cargo.toml"),
+        "[package]\nname = \"cli\"\nversion = \"1.0.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"=1.0.0\" }\ndep = { version = \"1.0.0\" }\n",
+    )?;
+
+    assert_version(repo, &["version", "--bump", "--write"], "1.1.0")?;
+
+    assert_eq!(
+        fs::read_to_string(repo.join("Cargo.toml"))?,
+        "[workspace]\nmembers = [\"crates/*\"]\n\n[workspace.package]\nversion = \"1.1.0\"\n\n[workspace.dependencies]\ncore = { path = \"crates/core\", version = \"1.1.0\" }\ndep = \"1.0.0\"\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("crates/core/Cargo.toml"))?,
+        "[package]\nname = \"core\"\nversion.workspace = true\n"
+    );
+    assert_eq!(
+        fs::read_to_string(repo.join("crates/cli/Cargo.toml"))?,
+        "[package]\nname = \"cli\"\nversion = \"1.1.0\"\n\n[dependencies]\ncore = { path = \"../core\", version = \"=1.1.0\" }\ndep = { version = \"1.0.0\" }\n"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn calver_scheme_bumps_to_the_current_month() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "versionScheme:\n  type: calver\n  format: YYYY.MM.MICRO\n",
+    )?;
+    git(repo, &["tag", "v2000.1.4"])?;
+    git(repo, &["tag", "v1.2.3.4"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "fix: next"])?;
+
+    let today = jiff::Zoned::now();
+    let this_month = format!("{}.{}", today.year(), today.month());
+    assert_version(repo, &["version"], "2000.1.4")?;
+    assert_version(repo, &["version", "--bump"], &format!("{this_month}.0"))?;
+
+    git(repo, &["tag", &format!("v{this_month}.0")])?;
+    git(repo, &["commit", "--allow-empty", "-m", "fix: again"])?;
+    assert_version(repo, &["version", "--bump"], &format!("{this_month}.1"))?;
+
+    Ok(())
+}
+
+#[test]
+fn regex_scheme_compares_numbers() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "versionScheme:\n  type: regex\n  pattern: '^(\\d+)-(\\d+)$'\n",
+    )?;
+    git(repo, &["tag", "v1-9"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: next"])?;
+    git(repo, &["tag", "v1-10"])?;
+
+    assert_version(repo, &["version"], "1.10")?;
+
+    Ok(())
+}
+
+#[test]
+fn tag_pattern_and_template_are_used_for_versions() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        "tagPattern: '^(?<version>.+)-final$'\ntagTemplate: '{{version}}-final'\n",
+    )?;
+    git(repo, &["tag", "release-1.2.3-final"])?;
+    git(repo, &["tag", "release-2.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat: next"])?;
+
+    assert_version(
+        repo,
+        &[
+            "version",
+            "--prefix",
+            "release-",
+            "--bump",
+            "--print-prefix",
+        ],
+        "release-1.3.0-final",
+    )?;
+
+    let output = run_convco_command(
+        &["version", "--prefix", "release-", "--verbose"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(
+        output.contains("ignored tag release-2.0.0: does not match the tagPattern"),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn explain_lists_the_commits_that_decide_the_bump() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    fs::create_dir_all(repo.join("src"))?;
+    for (path, message) in [
+        ("src/fix.txt", "fix: first"),
+        ("src/api.txt", "feat!: api"),
+        ("docs.txt", "docs: skipped"),
+        ("src/wip.txt", "wip"),
+    ] {
+        fs::write(repo.join(path), message)?;
+        git(repo, &["add", path])?;
+        git(repo, &["commit", "-m", message])?;
+    }
+
+    let output = run_convco_command(
+        &["version", "--bump", "--explain", "--paths", "src"],
+        Some(repo),
+        true,
+        "",
+    )?;
+    assert!(output.starts_with("stdout:\n2.0.0\n---"), "got:\n{output}");
+    for line in [
+        "<OID> wip [not conventional]\n",
+        "<OID> feat!: api [minor, breaking]\n",
+        "<OID> fix: first [patch]\n",
+        "<OID> docs: skipped [skipped by --paths]\n",
+        "major: decided by <OID>\n",
+    ] {
+        assert!(output.contains(line), "missing {line:?} in:\n{output}");
+    }
+
+    Ok(())
+}
+
+#[test]
+fn bump_rules_override_the_increment_of_the_type() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        r#"
+bumpRules:
+  - type: fix
+    scope: api
+    increment: Minor
+  - scope: deps
+    breaking: true
+    increment: None
+  - scope: deps
+    increment: None
+  - footer: Bump
+    value: none
+    increment: None
+  - footer: Release-As
+    releaseAs: true
+"#,
+    )?;
+    git(repo, &["tag", "v1.0.0"])?;
+    git(
+        repo,
+        &["commit", "--allow-empty", "-m", "feat(deps)!: bump"],
+    )?;
+    git(
+        repo,
+        &["commit", "--allow-empty", "-m", "feat: x\n\nBump: none"],
+    )?;
+    assert_version(repo, &["version", "--bump"], "1.0.0")?;
+
+    git(repo, &["commit", "--allow-empty", "-m", "fix(api): y"])?;
+    let output = run_convco_command(&["version", "--bump", "--explain"], Some(repo), true, "")?;
+    assert!(output.starts_with("stdout:\n1.1.0\n---"), "got:\n{output}");
+    for line in [
+        "<OID> fix(api): y [minor, bump rule 1: type fix, scope api]\n",
+        "<OID> feat: x [none, bump rule 4: footer Bump: none]\n",
+        "<OID> feat(deps)!: bump [none, breaking, bump rule 2: scope deps, breaking]\n",
+        "minor: decided by <OID>\n",
+    ] {
+        assert!(output.contains(line), "missing {line:?} in:\n{output}");
+    }
+
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix: z\n\nRelease-As: 3.0.0",
+        ],
+    )?;
+    assert_version(repo, &["version", "--bump"], "3.0.0")?;
+    assert_version(repo, &["version", "--bump", "--label"], "major")?;
+
+    Ok(())
+}
+
+#[test]
+fn bump_rules_keep_breaking_changes_major() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    fs::write(
+        repo.join(".convco"),
+        r#"
+bumpRules:
+  - type: fix
+    scope: api
+    increment: Minor
+  - scope: deps
+    increment: None
+"#,
+    )?;
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "fix(api)!: y"])?;
+    assert_version(repo, &["version", "--bump"], "2.0.0")?;
+
+    git(repo, &["tag", "v2.0.0"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "chore(deps)!: drop node 18",
+        ],
+    )?;
+    assert_version(repo, &["version", "--bump"], "3.0.0")?;
+
+    Ok(())
+}
+
+#[test]
+fn release_as_footer_sets_the_next_version() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base\n\nRelease-As: 1.0.0"])?;
+    let repo = temp.path();
+    assert_version(repo, &["version", "--bump"], "1.0.0")?;
+
+    git(repo, &["tag", "v1.0.0"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix: x\n\nrelease-as: 2.0.0",
+        ],
+    )?;
+    git(repo, &["commit", "--allow-empty", "-m", "feat!: y"])?;
+    assert_version(repo, &["version", "--bump"], "2.0.0")?;
+    assert_version(repo, &["version", "--bump", "--label"], "major")?;
+
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix: z\n\nRelease-As: 0.9.0",
+        ],
+    )?;
+    let output = run_convco_command(&["version", "--bump"], Some(repo), false, "")?;
+    assert!(
+        output.contains("Release-As 0.9.0 does not move forward from 1.0.0"),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn commit_filters_leave_out_bots_and_trailers() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "fix: a"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "--author",
+            "dependabot[bot] <support@github.com>",
+            "-m",
+            "feat: bump deps",
+        ],
+    )?;
+    assert_version(repo, &["version", "--bump"], "1.1.0")?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--exclude-author", "dependabot"],
+        "1.0.1",
+    )?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--author", "dependabot"],
+        "1.1.0",
+    )?;
+
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "feat!: internal\n\nSkip-Changelog: true",
+        ],
+    )?;
+    assert_version(
+        repo,
+        &[
+            "version",
+            "--bump",
+            "--exclude-trailer",
+            "skip-changelog",
+            "--exclude-author",
+            "dependabot",
+        ],
+        "1.0.1",
+    )?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--include-trailer", "Skip-Changelog"],
+        "2.0.0",
+    )?;
+    assert_version(
+        repo,
+        &["version", "--bump", "--since", "2999-01-01"],
+        "1.0.0",
+    )?;
+
+    let output = run_convco_command(
+        &["version", "--bump", "--until", "yesterday"],
+        Some(repo),
+        false,
+        "",
+    )?;
+    assert!(
+        output.contains("invalid date `yesterday`"),
+        "got:\n{output}"
+    );
+
+    Ok(())
+}
+
+#[test]
+fn bump_follows_squash_handling() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "fix: squashed (#12)\n\n* feat(api): add endpoint\n\n* wip",
+        ],
+    )?;
+
+    assert_version(repo, &["version", "--bump"], "1.0.1")?;
+    fs::write(repo.join(".convco"), "squashHandling: expand\n")?;
+    assert_version(repo, &["version", "--bump"], "1.1.0")?;
+
+    git(repo, &["tag", "v1.1.0"])?;
+    git(repo, &["checkout", "-q", "-b", "topic"])?;
+    git(repo, &["commit", "--allow-empty", "-m", "wip"])?;
+    git(repo, &["checkout", "-q", "-"])?;
+    git(
+        repo,
+        &[
+            "merge",
+            "--no-ff",
+            "topic",
+            "-m",
+            "Merge pull request #42 from owner/topic\n\nfeat: pull request title",
+        ],
+    )?;
+
+    assert_version(repo, &["version", "--bump"], "1.1.0")?;
+    fs::write(repo.join(".convco"), "squashHandling: prTitle\n")?;
+    assert_version(repo, &["version", "--bump"], "1.2.0")?;
+
+    Ok(())
+}
+
+#[test]
+fn breaking_squash_subject_bumps_major_with_expand() -> Result<(), Box<dyn std::error::Error>> {
+    let temp = setup_repo_with_commits(&["feat: base"])?;
+    let repo = temp.path();
+    git(repo, &["tag", "v1.0.0"])?;
+    git(
+        repo,
+        &[
+            "commit",
+            "--allow-empty",
+            "-m",
+            "feat(api)!: drop v1 endpoints (#12)\n\n* fix: handle null\n\n* docs: update",
+        ],
+    )?;
+    fs::write(repo.join(".convco"), "squashHandling: expand\n")?;
+
+    assert_version(repo, &["version", "--bump"], "2.0.0")?;
+
+    Ok(())
+}